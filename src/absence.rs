@@ -0,0 +1,241 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs::{ self, File },
+    io::BufReader,
+    path::PathBuf,
+};
+
+use chrono::{ Datelike, NaiveDate, TimeZone, Utc };
+use serde::{ Deserialize, Serialize };
+
+use crate::{ write_json, Folder, TrackerError, WorkPerDayInMinutes };
+
+const ABSENCE_FILE: &str = ".trackrs-absences";
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
+pub enum AbsenceType {
+    Vacation,
+    Sick,
+    Holiday,
+    /// Compensatory time off, taken from the overtime balance.
+    CompTime,
+}
+
+impl AbsenceType {
+    /// Whether the expected work time of the day is credited.
+    ///
+    /// Compensatory time off keeps the expected work time, so the day is paid by overtime.
+    pub fn credited(&self) -> bool {
+        !matches!(self, AbsenceType::CompTime)
+    }
+}
+
+impl Display for AbsenceType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AbsenceType::Vacation => "vacation",
+            AbsenceType::Sick => "sick leave",
+            AbsenceType::Holiday => "public holiday",
+            AbsenceType::CompTime => "comp time",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Absences by day, stored next to the time files.
+#[derive(Default, Clone, Debug, Serialize, Deserialize)]
+pub struct Absences {
+    pub data: BTreeMap<NaiveDate, AbsenceType>,
+    #[serde(skip)]
+    file: PathBuf,
+}
+
+impl Absences {
+    pub fn builder() -> AbsencesBuilder {
+        AbsencesBuilder::default()
+    }
+
+    pub fn get(&self, date: &NaiveDate) -> Option<AbsenceType> {
+        self.data.get(date).copied()
+    }
+
+    /// Mark every working day between `from` and `to` as absent, days without expected work time are skipped.
+    pub fn add(
+        &mut self,
+        from: NaiveDate,
+        to: NaiveDate,
+        r#type: AbsenceType,
+        workperday: &WorkPerDayInMinutes
+    ) -> Result<&mut Self, TrackerError> {
+        Absences::assert_range(from, to)?;
+        for d in from.iter_days().take_while(|d| d <= &to) {
+            let date = Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0).unwrap());
+            if workperday.from_date(date) == &0 {
+                log::debug!("skip {} without expected work time", d);
+                continue;
+            }
+            log::debug!("add {} on {}", r#type, d);
+            self.data.insert(d, r#type);
+        }
+        Ok(self)
+    }
+
+    pub fn remove(&mut self, from: NaiveDate, to: NaiveDate) -> Result<&mut Self, TrackerError> {
+        Absences::assert_range(from, to)?;
+        self.data.retain(|d, _| d < &from || d > &to);
+        Ok(self)
+    }
+
+    /// Days of vacation taken in `year`.
+    pub fn vacation_days(&self, year: i32) -> usize {
+        self.data
+            .iter()
+            .filter(|(d, t)| d.year() == year && **t == AbsenceType::Vacation)
+            .count()
+    }
+
+    pub fn write_to_file(&self) -> Result<(), TrackerError> {
+        log::debug!("write absences to {:?}", &self.file);
+        if let Some(folder) = self.file.parent() {
+            fs::create_dir_all(folder)?;
+        }
+        write_json(&self.file, &self, false)
+    }
+
+    fn assert_range(from: NaiveDate, to: NaiveDate) -> Result<(), TrackerError> {
+        if from > to {
+            return Err(TrackerError::AbsenceError {
+                message: format!("absence start {} is after absence end {}", from, to),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl Display for Absences {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.data
+            .iter()
+            .map(|(d, t)| format!("{}  {}", d.format("%a %Y-%m-%d"), t))
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[derive(Default)]
+pub struct AbsencesBuilder {
+    folder: Option<Folder>,
+}
+
+impl AbsencesBuilder {
+    pub fn folder(&mut self, folder: Folder) -> &mut Self {
+        self.folder = Some(folder);
+        self
+    }
+
+    /// Read the absences of the folder, an absent file has no absences.
+    pub fn build(&self) -> Result<Absences, TrackerError> {
+        let folder: PathBuf = match self.folder.to_owned() {
+            Some(f) => f.into(),
+            None => {
+                return Err(TrackerError::AbsenceError {
+                    message: "folder is not defined".to_owned(),
+                });
+            }
+        };
+        let file = folder.join(ABSENCE_FILE);
+        let mut absences = if file.exists() {
+            log::debug!("read absences from {:?}", &file);
+            serde_json::from_reader(BufReader::new(File::open(&file)?))?
+        } else {
+            Absences::default()
+        };
+        absences.file = file;
+        Ok(absences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2022, 3, day).unwrap()
+    }
+
+    mod absences {
+        use super::*;
+
+        #[test]
+        fn should_skip_days_without_work() -> Result<(), TrackerError> {
+            logger();
+            let mut absences = Absences::default();
+            // friday to tuesday
+            absences.add(date(11), date(15), AbsenceType::Vacation, &WorkPerDayInMinutes::default())?;
+
+            assert_eq!(3, absences.data.len());
+            assert_eq!(None, absences.get(&date(12)));
+            assert_eq!(Some(AbsenceType::Vacation), absences.get(&date(14)));
+            assert_eq!(3, absences.vacation_days(2022));
+            assert_eq!(0, absences.vacation_days(2023));
+            Ok(())
+        }
+
+        #[test]
+        fn should_replace_and_remove() -> Result<(), TrackerError> {
+            logger();
+            let workperday = WorkPerDayInMinutes::default();
+            let mut absences = Absences::default();
+            absences
+                .add(date(14), date(16), AbsenceType::Vacation, &workperday)?
+                .add(date(15), date(15), AbsenceType::Sick, &workperday)?;
+            assert_eq!(Some(AbsenceType::Sick), absences.get(&date(15)));
+            assert_eq!(2, absences.vacation_days(2022));
+
+            absences.remove(date(15), date(16))?;
+            assert_eq!(1, absences.data.len());
+            Ok(())
+        }
+
+        #[test]
+        fn invalid_range() {
+            let res = Absences::default().remove(date(16), date(15)).map(|_| ());
+            assert_eq!(
+                "absence error: absence start 2022-03-16 is after absence end 2022-03-15",
+                res.err().unwrap().to_string()
+            );
+        }
+
+        #[test]
+        fn should_credit() {
+            assert!(AbsenceType::Holiday.credited());
+            assert!(!AbsenceType::CompTime.credited());
+        }
+    }
+
+    mod file {
+        use super::*;
+
+        #[test]
+        fn should_write_and_read() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let folder: Folder = temp_dir.path().to_path_buf().into();
+
+            let mut absences = Absences::builder().folder(folder.to_owned()).build()?;
+            assert!(absences.data.is_empty());
+            absences.add(date(14), date(14), AbsenceType::Holiday, &WorkPerDayInMinutes::default())?;
+            absences.write_to_file()?;
+
+            let absences = Absences::builder().folder(folder).build()?;
+            assert_eq!(Some(AbsenceType::Holiday), absences.get(&date(14)));
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use std::{ fs::{ self, File }, io::{ BufWriter, Write }, path::Path, process };
+
+use serde::Serialize;
+
+use crate::TrackerError;
+
+/// Write `value` as JSON to `file` without leaving a partially written file behind.
+///
+/// The content goes to a temporary file in the same folder, is synced to disk and then renamed over `file`.
+pub(crate) fn write_json<T: Serialize>(file: &Path, value: &T, pretty: bool) -> Result<(), TrackerError> {
+    let folder = match file.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+        _ => std::env::current_dir()?,
+    };
+    let name = match file.file_name() {
+        Some(n) => n.to_string_lossy().to_string(),
+        None => {
+            return Err(TrackerError::TimeDataError {
+                message: format!("{:?} is not a file", file),
+            });
+        }
+    };
+    let temp = folder.join(format!(".{}.{}.tmp", name, process::id()));
+    log::trace!("write {:?} using {:?}", file, temp);
+
+    let result = (|| -> Result<(), TrackerError> {
+        let mut w = BufWriter::new(File::create(&temp)?);
+        if pretty {
+            serde_json::to_writer_pretty(&mut w, value)?;
+        } else {
+            serde_json::to_writer(&mut w, value)?;
+        }
+        w.flush()?;
+        w.get_ref().sync_all()?;
+        fs::rename(&temp, file)?;
+        Ok(())
+    })();
+
+    if result.is_err() && temp.exists() {
+        let _ = fs::remove_file(&temp);
+    }
+    result?;
+
+    // persist the rename itself, not supported for folders on every platform
+    if let Ok(f) = File::open(&folder) {
+        let _ = f.sync_all();
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_replace_without_temp_file() -> Result<(), TrackerError> {
+        let temp_dir = tempfile::tempdir()?;
+        let file = temp_dir.path().join("20220202.json");
+        fs::write(&file, "[\"a long content which is longer than the new one\"]")?;
+
+        write_json(&file, &["short"], false)?;
+
+        assert_eq!("[\"short\"]", fs::read_to_string(&file)?);
+        assert_eq!(1, fs::read_dir(temp_dir.path())?.count());
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_touch_target_on_error() -> Result<(), TrackerError> {
+        let temp_dir = tempfile::tempdir()?;
+        let file = temp_dir.path().join("missing").join("20220202.json");
+        assert!(write_json(&file, &["short"], false).is_err());
+        assert!(!file.exists());
+        Ok(())
+    }
+}
@@ -0,0 +1,545 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    fs::{ self, File },
+    io::BufReader,
+    path::{ Path, PathBuf },
+    time::UNIX_EPOCH,
+};
+
+use chrono::{ Datelike, Duration, NaiveDate };
+use serde::{ Deserialize, Serialize };
+
+use crate::{
+    fmt_overtime,
+    Absences,
+    Settings,
+    StatusRangeBuilder,
+    Status,
+    StatusTime,
+    TimeData,
+    TimeDataRangeBuilder,
+    TrackerError,
+    write_json,
+};
+
+const CACHE_FILE: &str = ".trackrs-balance";
+
+/// Running overtime balance over all time files.
+#[derive(Clone, Default, Debug)]
+pub struct Balance {
+    pub start: StatusTime,
+    pub until: Option<NaiveDate>,
+    pub months: Vec<BalanceMonth>,
+    pub total: StatusTime,
+}
+
+/// Work time, overtime and running balance at the end of a month.
+#[derive(Clone, Default, Debug)]
+pub struct BalanceMonth {
+    pub year: i32,
+    pub month: u32,
+    pub worktime: StatusTime,
+    pub overtime: StatusTime,
+    pub balance: StatusTime,
+}
+
+impl Balance {
+    pub fn builder() -> BalanceBuilder {
+        BalanceBuilder::default()
+    }
+}
+
+/// Cached results of time files, so old files are not parsed on every call.
+#[derive(Default, Deserialize, Serialize, PartialEq, Eq, Clone)]
+struct BalanceCache {
+    /// Settings the results were calculated with.
+    settings: String,
+    days: BTreeMap<NaiveDate, BalanceCacheDay>,
+}
+
+#[derive(Deserialize, Serialize, PartialEq, Eq, Clone)]
+struct BalanceCacheDay {
+    /// Modification time of the time file in nanoseconds.
+    modified: u64,
+    worktime: i64,
+    overtime: i64,
+}
+
+#[derive(Default)]
+pub struct BalanceBuilder {
+    settings: Option<Settings>,
+    until: Option<NaiveDate>,
+}
+
+impl BalanceBuilder {
+    pub fn settings(&mut self, settings: Settings) -> &mut Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// Last day of the balance, inclusive.
+    pub fn until(&mut self, until: NaiveDate) -> &mut Self {
+        self.until = Some(until);
+        self
+    }
+
+    pub fn build(&self) -> Result<Balance, TrackerError> {
+        let settings = match self.settings.to_owned() {
+            Some(s) => s,
+            None => {
+                return Err(TrackerError::BalanceError {
+                    message: "settings not defined".to_owned(),
+                });
+            }
+        };
+        let until = match self.until {
+            Some(u) => u,
+            None => {
+                return Err(TrackerError::BalanceError {
+                    message: "until not defined".to_owned(),
+                });
+            }
+        };
+
+        let folder = PathBuf::from(&settings.folder);
+        let files = TimeDataRangeBuilder::files(&folder)?;
+
+        let start = StatusTime::from(Duration::minutes(settings.balance.start));
+        let first = match settings.balance.reset.or(files.keys().next().copied()) {
+            Some(f) if f <= until => f,
+            _ => {
+                log::debug!("no time files for balance found");
+                return Ok(Balance {
+                    total: start.to_owned(),
+                    start,
+                    until: Some(until),
+                    ..Default::default()
+                });
+            }
+        };
+
+        let cache_file = folder.join(CACHE_FILE);
+        let absences = Absences::builder().folder(settings.folder.to_owned().into()).build()?;
+        let fingerprint = BalanceBuilder::fingerprint(&settings, &absences)?;
+        let mut cache = BalanceBuilder::read_cache(&cache_file, &fingerprint);
+        let cached = cache.to_owned();
+
+        let mut months: Vec<BalanceMonth> = Vec::new();
+        let mut total = start.to_owned();
+
+        for date in TimeDataRangeBuilder::dates(first, until) {
+            let day = date.date_naive();
+            let (worktime, overtime) = match files.get(&day) {
+                Some(file) => {
+                    let modified = BalanceBuilder::modified(file)?;
+                    match cache.days.get(&day) {
+                        Some(c) if c.modified == modified => {
+                            log::trace!("use cached balance of {}", day);
+                            (c.worktime, c.overtime)
+                        }
+                        _ => {
+                            log::debug!("calculate balance of {}", day);
+                            let mut t = TimeData::builder()
+                                .folder(settings.folder.to_owned().into())
+                                .day(day)
+                                .build()?;
+                            t.read_only()?;
+                            let (s, _) = StatusRangeBuilder::daily(&t, &settings)?;
+                            let c = BalanceCacheDay {
+                                modified,
+                                worktime: s.worktime.duration.num_minutes(),
+                                overtime: s.overtime.duration.num_minutes(),
+                            };
+                            // work time of an open day grows until it ends
+                            if t.entries.data.last().is_some_and(|e| e.status == Status::End) {
+                                cache.days.insert(day, c.to_owned());
+                            }
+                            (c.worktime, c.overtime)
+                        }
+                    }
+                }
+                None => {
+                    let t = TimeData {
+                        date: Some(date),
+                        absence: absences.get(&day),
+                        ..Default::default()
+                    };
+                    let (s, _) = StatusRangeBuilder::daily(&t, &settings)?;
+                    (0, s.overtime.duration.num_minutes())
+                }
+            };
+
+            if months.last().map(|m| (m.year, m.month)) != Some((day.year(), day.month())) {
+                months.push(BalanceMonth {
+                    year: day.year(),
+                    month: day.month(),
+                    ..Default::default()
+                });
+            }
+            let m = months.last_mut().unwrap();
+            m.worktime += StatusTime::from(Duration::minutes(worktime));
+            m.overtime += StatusTime::from(Duration::minutes(overtime));
+            total += StatusTime::from(Duration::minutes(overtime));
+            m.balance = total.to_owned();
+        }
+
+        // results of unchanged files are kept for the next call
+        cache.days.retain(|d, _| files.contains_key(d));
+        if cache != cached {
+            BalanceBuilder::write_cache(&cache_file, &cache)?;
+        }
+
+        log::info!("overtime balance is {}", total);
+
+        Ok(Balance {
+            start,
+            until: Some(until),
+            months,
+            total,
+        })
+    }
+
+    /// Modification time of `file` in nanoseconds, a cached result is only used while it is unchanged.
+    fn modified(file: &Path) -> Result<u64, TrackerError> {
+        let modified = fs::metadata(file)?.modified()?;
+        Ok(modified.duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos() as u64)
+    }
+
+    /// Settings, holidays and absences which change the result of a day.
+    fn fingerprint(settings: &Settings, absences: &Absences) -> Result<String, TrackerError> {
+        Ok(
+            serde_json::to_string(
+                &(
+                    &settings.threshold_limits,
+                    &settings.limits,
+                    &settings.workperday,
+                    &settings.workperday.holidays,
+                    &settings.timezone,
+                    &absences.data,
+                )
+            )?
+        )
+    }
+
+    fn read_cache(file: &Path, fingerprint: &str) -> BalanceCache {
+        let cache = File::open(file)
+            .ok()
+            .and_then(|f| serde_json::from_reader::<_, BalanceCache>(BufReader::new(f)).ok());
+        match cache {
+            Some(c) if c.settings == fingerprint => c,
+            _ => {
+                log::debug!("balance cache not available, calculate all days");
+                BalanceCache {
+                    settings: fingerprint.to_owned(),
+                    ..Default::default()
+                }
+            }
+        }
+    }
+
+    fn write_cache(file: &Path, cache: &BalanceCache) -> Result<(), TrackerError> {
+        log::debug!("write balance cache to {:?}", file);
+        write_json(file, cache, false)
+    }
+}
+
+impl Balance {
+    /// Single line summary as shown by the daily status.
+    pub fn summary(&self) -> String {
+        match self.until {
+            Some(u) => format!("{:13}{} (until {})", "Balance:", fmt_overtime(&self.total), u),
+            None => format!("{:13}{}", "Balance:", fmt_overtime(&self.total)),
+        }
+    }
+
+    pub fn month(&self, year: i32, month: u32) -> Option<&BalanceMonth> {
+        self.months.iter().find(|m| m.year == year && m.month == month)
+    }
+}
+
+impl Display for Balance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = 10;
+
+        let mut lines = vec![
+            format!(" {:width$} | {:width$} | {:width$} | {:width$}", "Month", "Work time", "Overtime", "Balance"),
+            format!(" {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$}", ""),
+            format!(
+                " {0:width$} | {1: >width$} | {2: >width$} | {3: >width$}",
+                "Start",
+                "",
+                "",
+                fmt_overtime(&self.start)
+            )
+        ];
+
+        self.months.iter().for_each(|m| {
+            lines.push(
+                format!(
+                    " {0:width$} | {1: >width$} | {2: >width$} | {3: >width$}",
+                    format!("{:04}-{:02}", m.year, m.month),
+                    format!("{}", m.worktime),
+                    fmt_overtime(&m.overtime),
+                    fmt_overtime(&m.balance)
+                )
+            );
+        });
+        writeln!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use crate::{ BalanceSettings, WorkSchedule };
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn write_day(folder: &Path, day: u32, end: &str) -> Result<(), TrackerError> {
+        let content = format!(
+            "{{\"data\":[{{\"id\":1,\"status\":\"Connect\",\"time\":\"2022-03-{0:02}T08:00:00Z\"}},{{\"id\":2,\"status\":\"End\",\"time\":\"2022-03-{0:02}T{1}:00Z\"}}],\"version\":1}}",
+            day,
+            end
+        );
+        let mut file = File::create(folder.join(format!("202203{:02}.json", day)))?;
+        file.write_all(content.as_bytes())?;
+        Ok(())
+    }
+
+    fn get_settings(folder: &Path) -> Settings {
+        Settings {
+            folder: folder.to_str().unwrap().to_owned(),
+            ..Default::default()
+        }
+    }
+
+    mod builder {
+        use super::*;
+
+        #[test]
+        fn should_calculate_balance() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            // tuesday to thursday, wednesday is missing
+            write_day(temp_dir.path(), 1, "17:00")?;
+            write_day(temp_dir.path(), 3, "18:30")?;
+
+            let mut settings = get_settings(temp_dir.path());
+            settings.balance.start = 60;
+
+            let balance = Balance::builder()
+                .settings(settings)
+                .until(NaiveDate::from_ymd_opt(2022, 3, 3).unwrap())
+                .build()?;
+
+            log::debug!("{}", balance);
+
+            // +1h start, +1h tuesday, -8h wednesday, +2:30h thursday
+            assert_eq!(StatusTime::from(Duration::minutes(-210)), balance.total);
+            assert_eq!(1, balance.months.len());
+            assert_eq!(StatusTime::from(Duration::minutes(1170)), balance.months[0].worktime);
+            Ok(())
+        }
+
+        #[test]
+        fn should_calculate_balance_per_month() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            write_day(temp_dir.path(), 1, "17:00")?;
+
+            let balance = Balance::builder()
+                .settings(get_settings(temp_dir.path()))
+                .until(NaiveDate::from_ymd_opt(2022, 4, 1).unwrap())
+                .build()?;
+
+            assert_eq!(2, balance.months.len());
+            let april = balance.month(2022, 4).unwrap();
+            // friday the 1st of april is missing
+            assert_eq!(StatusTime::from(Duration::hours(-8)), april.overtime);
+            assert_eq!(balance.total, april.balance);
+            Ok(())
+        }
+
+        #[test]
+        fn should_start_at_reset() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            write_day(temp_dir.path(), 1, "17:00")?;
+            write_day(temp_dir.path(), 2, "17:00")?;
+
+            let mut settings = get_settings(temp_dir.path());
+            settings.balance = BalanceSettings {
+                start: 0,
+                reset: NaiveDate::from_ymd_opt(2022, 3, 2),
+            };
+
+            let balance = Balance::builder()
+                .settings(settings)
+                .until(NaiveDate::from_ymd_opt(2022, 3, 2).unwrap())
+                .build()?;
+
+            assert_eq!(StatusTime::from(Duration::hours(1)), balance.total);
+            Ok(())
+        }
+
+        #[test]
+        fn should_credit_absences() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            write_day(temp_dir.path(), 1, "17:00")?;
+            let settings = get_settings(temp_dir.path());
+
+            let mut absences = Absences::builder().folder(temp_dir.path().to_path_buf().into()).build()?;
+            absences
+                .add(
+                    NaiveDate::from_ymd_opt(2022, 3, 2).unwrap(),
+                    NaiveDate::from_ymd_opt(2022, 3, 2).unwrap(),
+                    crate::AbsenceType::Vacation,
+                    &settings.workperday
+                )?
+                .add(
+                    NaiveDate::from_ymd_opt(2022, 3, 3).unwrap(),
+                    NaiveDate::from_ymd_opt(2022, 3, 3).unwrap(),
+                    crate::AbsenceType::CompTime,
+                    &settings.workperday
+                )?
+                .write_to_file()?;
+
+            let balance = Balance::builder()
+                .settings(settings)
+                .until(NaiveDate::from_ymd_opt(2022, 3, 3).unwrap())
+                .build()?;
+
+            // +1h tuesday, vacation on wednesday, comp time on thursday
+            assert_eq!(StatusTime::from(Duration::hours(-7)), balance.total);
+            Ok(())
+        }
+
+        #[test]
+        fn should_use_start_without_files() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let mut settings = get_settings(temp_dir.path());
+            settings.balance.start = -30;
+
+            let balance = Balance::builder()
+                .settings(settings)
+                .until(NaiveDate::from_ymd_opt(2022, 3, 2).unwrap())
+                .build()?;
+
+            assert_eq!(StatusTime::from(Duration::minutes(-30)), balance.total);
+            assert!(balance.months.is_empty());
+            Ok(())
+        }
+    }
+
+    mod cache {
+        use super::*;
+
+        #[test]
+        fn should_write_cache() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            write_day(temp_dir.path(), 1, "17:00")?;
+
+            let settings = get_settings(temp_dir.path());
+            Balance::builder()
+                .settings(settings.to_owned())
+                .until(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap())
+                .build()?;
+
+            let cache = BalanceBuilder::read_cache(
+                &temp_dir.path().join(CACHE_FILE),
+                &BalanceBuilder::fingerprint(&settings, &Absences::default())?
+            );
+            let day = cache.days.get(&NaiveDate::from_ymd_opt(2022, 3, 1).unwrap()).unwrap();
+            assert_eq!(9 * 60, day.worktime);
+            assert_eq!(60, day.overtime);
+
+            // an unchanged cache is not written again
+            let modified = BalanceBuilder::modified(&temp_dir.path().join(CACHE_FILE))?;
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            Balance::builder()
+                .settings(settings.to_owned())
+                .until(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap())
+                .build()?;
+            assert_eq!(modified, BalanceBuilder::modified(&temp_dir.path().join(CACHE_FILE))?);
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_cache_open_day() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            write_day(temp_dir.path(), 1, "17:00")?;
+            let content =
+                "{\"data\":[{\"id\":1,\"status\":\"Connect\",\"time\":\"2022-03-02T08:00:00Z\"}],\"version\":1}";
+            fs::write(temp_dir.path().join("20220302.json"), content)?;
+
+            let settings = get_settings(temp_dir.path());
+            Balance::builder()
+                .settings(settings.to_owned())
+                .until(NaiveDate::from_ymd_opt(2022, 3, 2).unwrap())
+                .build()?;
+
+            let cache = BalanceBuilder::read_cache(
+                &temp_dir.path().join(CACHE_FILE),
+                &BalanceBuilder::fingerprint(&settings, &Absences::default())?
+            );
+            assert!(cache.days.contains_key(&NaiveDate::from_ymd_opt(2022, 3, 1).unwrap()));
+            assert!(!cache.days.contains_key(&NaiveDate::from_ymd_opt(2022, 3, 2).unwrap()));
+            Ok(())
+        }
+
+        #[test]
+        fn should_use_cache() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            write_day(temp_dir.path(), 1, "17:00")?;
+
+            let settings = get_settings(temp_dir.path());
+            let cache_file = temp_dir.path().join(CACHE_FILE);
+            let absences = Absences::builder().folder(settings.folder.to_owned().into()).build()?;
+            let fingerprint = BalanceBuilder::fingerprint(&settings, &absences)?;
+
+            // cached result with the current modification time takes precedence over the file
+            let mut cache = BalanceCache {
+                settings: fingerprint.to_owned(),
+                ..Default::default()
+            };
+            cache.days.insert(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap(), BalanceCacheDay {
+                modified: BalanceBuilder::modified(&temp_dir.path().join("20220301.json"))?,
+                worktime: 0,
+                overtime: 42,
+            });
+            BalanceBuilder::write_cache(&cache_file, &cache)?;
+
+            let balance = Balance::builder()
+                .settings(settings.to_owned())
+                .until(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap())
+                .build()?;
+            assert_eq!(StatusTime::from(Duration::minutes(42)), balance.total);
+
+            // changed settings invalidate the cache
+            let mut changed = settings.to_owned();
+            changed.workperday.tuesday = 9 * 60;
+            let balance = Balance::builder()
+                .settings(changed)
+                .until(NaiveDate::from_ymd_opt(2022, 3, 1).unwrap())
+                .build()?;
+            assert_eq!(StatusTime::from(Duration::minutes(0)), balance.total);
+
+            let mut scheduled = settings.to_owned();
+            scheduled.workperday.schedules.push(WorkSchedule { monday: 4 * 60, ..Default::default() });
+            assert_ne!(fingerprint, BalanceBuilder::fingerprint(&scheduled, &absences)?);
+            Ok(())
+        }
+    }
+}
@@ -1,9 +1,19 @@
-use chrono::{Datelike, IsoWeek, Local};
-use clap::{Parser, Subcommand};
+use std::{
+    env, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Datelike, Duration, NaiveDate, Utc};
+use clap::{Args, Parser, Subcommand};
+use config::ConfigError;
 use log::LevelFilter;
 
 use crate::{
-    entry::Status, Settings, StatusDaily, StatusWeekly, TimeData, TimeDataWeekly, TrackerError,
+    entry::Status, AbsenceType, Absences, Balance, Export, ExportFormat, ExportMode, Import, ImportFormat, Migration,
+    OutputFormat, RuleCheck, Settings, SettingsCheck, SettingsPaths, StatusDaily, StatusDailyOutput, StatusMonthly,
+    StatusRange, StatusWeekly, Takeover, TimeData, TimeDataMonthly, TimeDataRange, TimeDataWeekly, TimeExpression,
+    TrackerError, WorkTimeZone, PROFILE_ENV,
 };
 
 type TrackerResult = Result<(), TrackerError>;
@@ -20,6 +30,18 @@ pub struct Cli {
 
     #[clap(flatten)]
     verbose: clap_verbosity_flag::Verbosity,
+
+    /// Profile of the configuration to use, eg. per employer
+    ///
+    /// Defaults to the TRACKRS_PROFILE environment variable, without both the shared configuration is used.
+    #[clap(long, global = true)]
+    profile: Option<String>,
+
+    /// Configuration file to use
+    ///
+    /// Defaults to the TRACKRS_CONFIG environment variable, without both $XDG_CONFIG_HOME/trackrs/config.json is used.
+    #[clap(long, global = true, value_name = "FILE")]
+    config: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Debug)]
@@ -35,49 +57,214 @@ pub enum Commands {
         #[clap(short, value_parser, allow_hyphen_values = true)]
         week: Option<i8>,
 
-        /// Format week status as table.
+        /// Month to show the status for
+        ///
+        /// Either enter the month of the year (1 to 12) or a relative value eg. -1
+        #[clap(short, long, value_parser, allow_hyphen_values = true, conflicts_with = "week")]
+        month: Option<i8>,
+
+        /// Format week or month status as table.
         #[clap(short, long)]
         table: bool,
+
+        /// Output format of the day or week status, json and yaml are meant for scripts
+        #[clap(short, long, value_enum, default_value = "text", conflicts_with_all = ["month", "table"])]
+        output: OutputFormat,
     },
     /// Start tracking work
     ///
     /// Starts tracking work for today.
     #[clap(display_order = 2)]
-    Start,
+    Start {
+        #[clap(flatten)]
+        segment: SegmentArgs,
+
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
     /// Take a break
     ///
     /// Breaks current tracking.
     #[clap(display_order = 3)]
-    Break,
+    Break {
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
     /// End tracking work
     ///
     /// End tracking work for today.
     #[clap(display_order = 4)]
-    End,
+    End {
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
     /// Disconnect from work
     ///
     /// Simple disconnect from work. This will only create a disconnect entry in the tracking history.
     #[clap(display_order = 5)]
-    Disconnect,
+    Disconnect {
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
     /// Continue tracking work
     ///
     /// Continue tracking work for today.
     #[clap(display_order = 6)]
-    Continue,
+    Continue {
+        #[clap(flatten)]
+        segment: SegmentArgs,
+
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
+    /// Switch to another project
+    ///
+    /// Ends the current segment and continues tracking work for the given project.
+    #[clap(display_order = 7)]
+    Switch {
+        /// Project to continue with.
+        project: String,
+
+        /// Tag of the segment, can be repeated.
+        #[clap(long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+
+        /// Note of the segment
+        #[clap(short, long = "message", value_name = "NOTE")]
+        message: Option<String>,
+
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
+    /// Add a note
+    ///
+    /// Attaches a note to the current segment, or to the whole day with --day.
+    #[clap(display_order = 8)]
+    Note {
+        /// Text of the note.
+        text: String,
+
+        /// Attach the note to the whole day.
+        #[clap(long)]
+        day: bool,
+
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
     /// Take over time to next day
     ///
     /// Takes over defined minutes to next day, whenever next connect is executed.
-    #[clap(display_order = 7)]
+    #[clap(display_order = 9)]
     Takeover {
         /// Minutes to take over to next day.
         #[clap()]
         minutes: u16,
+
+        #[clap(flatten)]
+        track: TrackArgs,
+    },
+    /// Report for a date range
+    ///
+    /// Get the status for every day between two dates, eg. for sprints, quarters or pay periods.
+    #[clap(display_order = 10)]
+    Report {
+        /// First day of the report
+        ///
+        /// Either today, yesterday or a date like 2022-02-02.
+        #[clap(short, long)]
+        from: String,
+
+        /// Last day of the report, defaults to today
+        ///
+        /// Either today, yesterday or a date like 2022-02-02.
+        #[clap(short = 'u', long, default_value = "today")]
+        to: String,
+
+        /// Format report as table.
+        #[clap(short, long)]
+        table: bool,
+
+        /// Report every profile and their sum
+        #[clap(short, long, conflicts_with = "table")]
+        combined: bool,
+    },
+    /// Export entries or daily summaries
+    ///
+    /// Export every entry or one row per day between two dates, eg. for spreadsheets, or work segments as calendar
+    /// events.
+    #[clap(display_order = 11)]
+    Export {
+        #[clap(flatten)]
+        export: ExportArgs,
+    },
+    /// Import records of other time trackers
+    ///
+    /// Convert the records of Toggl, Clockify, Timewarrior or a generic CSV into entries. Days which already have
+    /// entries are not imported.
+    #[clap(display_order = 12)]
+    Import {
+        /// Format of the imported file
+        #[clap(long, value_enum)]
+        format: ImportFormat,
+
+        /// File to import
+        file: PathBuf,
+
+        /// Only list the days which would be imported
+        #[clap(long)]
+        dry_run: bool,
+    },
+    /// Overtime balance
+    ///
+    /// Get the accumulated overtime of all tracked days, starting at the configured balance, with a breakdown per month.
+    #[clap(display_order = 13)]
+    Balance {
+        /// Last day of the balance, defaults to yesterday
+        ///
+        /// Either today, yesterday or a date like 2022-02-02.
+        #[clap(short, long, default_value = "yesterday")]
+        until: String,
+    },
+    /// Absences like vacation or sick leave
+    ///
+    /// Absent days do not count as missing work time, except compensatory time off which is taken from the overtime.
+    #[clap(display_order = 14)]
+    Absence {
+        #[clap(subcommand)]
+        action: AbsenceCommands,
+    },
+    /// Edit entries of a day
+    ///
+    /// List, amend, delete or insert single entries of a day. The status of the day is shown afterwards.
+    #[clap(display_order = 15)]
+    Edit {
+        /// Day to edit
+        ///
+        /// Either today, yesterday or a date like 2022-02-02.
+        #[clap(short, long, default_value = "today")]
+        day: String,
+
+        #[clap(subcommand)]
+        action: EditCommands,
+    },
+    /// Move entries into the time file of their local day
+    ///
+    /// Time files were named by the UTC day before, so entries close to midnight may be stored in the
+    /// file of the previous or next day.
+    #[clap(display_order = 16)]
+    Migrate {
+        /// Only list the entries which would be moved
+        #[clap(long)]
+        dry_run: bool,
     },
     /// Configuration
     ///
-    /// List or edit configuration
-    #[clap(display_order = 8)]
+    /// List, edit or check configuration
+    #[clap(display_order = 17)]
     Config {
+        #[clap(subcommand)]
+        action: Option<ConfigCommands>,
+
         /// List configuration
         #[clap(short, long, conflicts_with = "edit")]
         list: bool,
@@ -87,6 +274,159 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum ConfigCommands {
+    /// Check the configuration and report every problem with its field
+    Check,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum EditCommands {
+    /// List entries with their id
+    List,
+    /// Change status and/or time of an entry
+    Set {
+        /// Id of the entry.
+        id: u8,
+
+        /// New status of the entry.
+        #[clap(short, long, value_enum)]
+        status: Option<Status>,
+
+        /// New time of the entry, eg. 08:15 or -10m.
+        #[clap(short, long, value_name = "TIME", allow_hyphen_values = true)]
+        time: Option<String>,
+    },
+    /// Delete an entry
+    Delete {
+        /// Id of the entry.
+        id: u8,
+    },
+    /// Insert a new entry
+    Insert {
+        /// Status of the entry.
+        #[clap(value_enum)]
+        status: Status,
+
+        /// Time of the entry, eg. 08:15 or -10m.
+        #[clap(allow_hyphen_values = true)]
+        time: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AbsenceCommands {
+    /// Add an absence for one or more days
+    Add {
+        /// Type of the absence.
+        #[clap(short = 't', long = "type", value_enum)]
+        r#type: AbsenceType,
+
+        /// First day of the absence, eg. today or 2022-02-02.
+        #[clap(short, long)]
+        from: String,
+
+        /// Last day of the absence, defaults to the first day.
+        #[clap(short = 'u', long)]
+        to: Option<String>,
+    },
+    /// Remove absences of one or more days
+    Remove {
+        /// First day to remove, eg. today or 2022-02-02.
+        #[clap(short, long)]
+        from: String,
+
+        /// Last day to remove, defaults to the first day.
+        #[clap(short = 'u', long)]
+        to: Option<String>,
+    },
+    /// List absences and remaining vacation days of a year
+    List {
+        /// Year to list, defaults to the current year.
+        #[clap(short, long)]
+        year: Option<i32>,
+    },
+}
+
+#[derive(Args, Debug, Default)]
+pub struct SegmentArgs {
+    /// Project of the tracked work
+    #[clap(short, long)]
+    project: Option<String>,
+
+    /// Tag of the tracked work, can be repeated.
+    #[clap(long = "tag", value_name = "TAG")]
+    tags: Vec<String>,
+
+    /// Note of the tracked work
+    #[clap(short, long = "message", value_name = "NOTE")]
+    message: Option<String>,
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TrackArgs {
+    /// Time of the entry instead of now
+    ///
+    /// Either a time of today (08:15), a relative time (-10m, +1h30m), a day with time (yesterday 17:30)
+    /// or a date with time (2022-02-02 08:15).
+    #[clap(long, value_name = "TIME", num_args = 1..=2, allow_hyphen_values = true)]
+    at: Option<Vec<String>>,
+}
+
+impl TrackArgs {
+    /// Resolve the time of the entry at the work location, defaults to now.
+    fn time(&self, zone: &WorkTimeZone) -> Result<DateTime<Utc>, TrackerError> {
+        let now = Utc::now();
+        match &self.at {
+            Some(at) => TimeExpression::from_str(&at.join(" "))?.resolve(now, zone),
+            None => Ok(now),
+        }
+    }
+}
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Format of the export
+    #[clap(long, value_enum, default_value = "csv")]
+    format: ExportFormat,
+
+    /// Export entries or one row per day
+    #[clap(short, long, value_enum, default_value = "entries")]
+    mode: ExportMode,
+
+    /// First day of the export
+    ///
+    /// Either today, yesterday or a date like 2022-02-02.
+    #[clap(short, long)]
+    from: String,
+
+    /// Last day of the export, defaults to today
+    ///
+    /// Either today, yesterday or a date like 2022-02-02.
+    #[clap(short = 'u', long, default_value = "today")]
+    to: String,
+
+    /// Columns to export, separated by comma
+    ///
+    /// Entries: date, id, status, time, project, tags, note.
+    /// Days: date, start, end, break, worktime, overtime, expected, notes, rounded_start, rounded_end,
+    /// rounded_worktime.
+    #[clap(short, long, value_delimiter = ',')]
+    columns: Vec<String>,
+
+    /// Delimiter of csv fields
+    #[clap(short, long, default_value = ",")]
+    delimiter: char,
+
+    /// Add breaks as separate calendar events
+    #[clap(short, long)]
+    breaks: bool,
+
+    /// File to write the export to, defaults to stdout
+    #[clap(short, long)]
+    output: Option<PathBuf>,
+}
+
 pub trait CliExecute {
     fn execute(&self) -> TrackerResult;
     fn init_logger(&self) -> TrackerResult;
@@ -95,14 +435,26 @@ pub trait CliExecute {
 impl CliExecute for Cli {
     fn execute(&self) -> TrackerResult {
         match &self.command {
-            Commands::Break => self.invoke_break(),
-            Commands::End => self.invoke_end(),
-            Commands::Disconnect => self.invoke_disconnect(),
-            Commands::Status { week, table } => self.invoke_status(week, table),
-            Commands::Config { list: _, edit } => self.invoke_config(edit),
-            Commands::Takeover { minutes } => self.invoke_takeover(minutes),
-            Commands::Start => self.invoke_start(),
-            _ => self.invoke_continue(), // default and Command::Start.
+            Commands::Break { track } => self.invoke_break(track),
+            Commands::End { track } => self.invoke_end(track),
+            Commands::Disconnect { track } => self.invoke_disconnect(track),
+            Commands::Status { week, month, table, output } => self.invoke_status(week, month, table, output),
+            Commands::Config { action: Some(ConfigCommands::Check), .. } => self.invoke_config_check(),
+            Commands::Config { action: None, list: _, edit } => self.invoke_config(edit),
+            Commands::Takeover { minutes, track } => self.invoke_takeover(minutes, track),
+            Commands::Start { segment, track } => self.invoke_start(segment, track),
+            Commands::Continue { segment, track } => self.invoke_continue(segment, track),
+            Commands::Switch { project, tags, message, track } => {
+                self.invoke_switch(project, tags, message, track)
+            }
+            Commands::Note { text, day, track } => self.invoke_note(text, day, track),
+            Commands::Report { from, to, table, combined } => self.invoke_report(from, to, table, combined),
+            Commands::Export { export } => self.invoke_export(export),
+            Commands::Import { format, file, dry_run } => self.invoke_import(format, file, dry_run),
+            Commands::Balance { until } => self.invoke_balance(until),
+            Commands::Absence { action } => self.invoke_absence(action),
+            Commands::Edit { day, action } => self.invoke_edit(day, action),
+            Commands::Migrate { dry_run } => self.invoke_migrate(dry_run),
         }
     }
 
@@ -116,9 +468,8 @@ impl CliExecute for Cli {
             logger.format_target(true);
         }
 
-        let err = logger.try_init();
-        if err.is_err() {
-            eprintln!("{:?}", err.unwrap_err());
+        if let Err(err) = logger.try_init() {
+            eprintln!("{:?}", err);
         }
 
         log::info!("Informational logging is active.");
@@ -130,96 +481,209 @@ impl CliExecute for Cli {
 }
 
 impl Cli {
-    fn invoke_start(&self) -> TrackerResult {
-        log::info!("start executed");
-        let settings = Settings::new()?;
-        let mut time_data = TimeData::builder()
-            .folder(settings.folder.into())
-            .today()
-            .build()?;
-        let now = Local::now();
-        time_data
-            .read_from_file()?
-            .assert_takeover(now.to_utc())?
-            .append(Status::Connect, now.to_utc())?
-            .write_to_file()
+    /// Settings of the profile given by --profile, otherwise of TRACKRS_PROFILE.
+    fn settings(&self) -> Result<Settings, TrackerError> {
+        self.profile_settings(self.profile.to_owned().or_else(|| env::var(PROFILE_ENV).ok()).as_deref())
     }
 
-    fn invoke_continue(&self) -> TrackerResult {
+    /// Settings of the profile, an invalid configuration is rejected by [`Settings::load`].
+    fn profile_settings(&self, profile: Option<&str>) -> Result<Settings, TrackerError> {
+        let paths = SettingsPaths::resolve(self.config.to_owned())?;
+        Ok(Settings::load(&paths, profile)?)
+    }
+
+    /// Time data of the day `time` falls into at the work location.
+    fn day_data(settings: &Settings, time: &DateTime<Utc>) -> Result<TimeData, TrackerError> {
+        TimeData::builder()
+            .folder(settings.folder.to_owned().into())
+            .takeover_file(settings.takeover_file())
+            .timezone(settings.timezone)
+            .day(settings.timezone.date(*time))
+            .build()
+    }
+
+    /// Time data of the session `time` belongs to, which is the previous day while a session
+    /// started there continues past midnight.
+    ///
+    /// The days are read with `read`, with [`TimeData::read_from_file`] the session is decided on the locked files.
+    fn time_data(
+        settings: &Settings,
+        time: &DateTime<Utc>,
+        read: fn(&mut TimeData) -> Result<&mut TimeData, TrackerError>,
+    ) -> Result<TimeData, TrackerError> {
+        let day = settings.timezone.date(*time);
+        let data = |d: NaiveDate| {
+            TimeData::builder()
+                .folder(settings.folder.to_owned().into())
+                .takeover_file(settings.takeover_file())
+                .timezone(settings.timezone)
+                .day(d)
+                .build()
+        };
+
+        let mut current = data(day)?;
+        read(&mut current)?;
+        if current.entries.data.is_empty() {
+            let previous_day = day.pred_opt().unwrap();
+            let mut previous = data(previous_day)?;
+            read(&mut previous)?;
+            if previous.continues_at(*time) {
+                log::info!("continue session of {}", previous_day);
+                current.unlock();
+                return Ok(previous);
+            }
+        }
+        Ok(current)
+    }
+
+    fn invoke_start(&self, segment: &SegmentArgs, track: &TrackArgs) -> TrackerResult {
         log::info!("start executed");
-        let settings = Settings::new()?;
-        let mut time_data = TimeData::builder()
-            .folder(settings.folder.into())
-            .today()
-            .build()?;
-        let now = Local::now();
-        time_data
-            .read_from_file()?
-            .append(Status::Connect, now.to_utc())?
-            .write_to_file()
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::day_data(&settings, &time)?;
+        let takeover = time_data.read_from_file()?.assert_takeover(time)?;
+        time_data.append_project(
+            Status::Connect,
+            time,
+            segment.project.to_owned(),
+            segment.tags.to_owned(),
+        )?;
+        if let Some(m) = segment.message.as_ref() {
+            time_data.note(m.to_owned())?;
+        }
+        time_data.write_to_file()?;
+        if let Some(m) = takeover {
+            // minutes are used, don't take them over again
+            log::info!("took over {} minutes", m);
+            time_data.clear_takeover()?;
+        }
+        Ok(())
+    }
+
+    fn invoke_continue(&self, segment: &SegmentArgs, track: &TrackArgs) -> TrackerResult {
+        log::info!("continue executed");
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::time_data(&settings, &time, TimeData::read_from_file)?;
+        time_data.append_project(
+            Status::Connect,
+            time,
+            segment.project.to_owned(),
+            segment.tags.to_owned(),
+        )?;
+        if let Some(m) = segment.message.as_ref() {
+            time_data.note(m.to_owned())?;
+        }
+        time_data.write_to_file()
+    }
+
+    fn invoke_note(&self, text: &str, day: &bool, track: &TrackArgs) -> TrackerResult {
+        log::info!("note executed");
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::time_data(&settings, &time, TimeData::read_from_file)?;
+        if *day {
+            time_data.day_note(text.to_owned())?;
+        } else {
+            time_data.note(text.to_owned())?;
+        }
+        time_data.write_to_file()
     }
 
-    fn invoke_break(&self) -> TrackerResult {
+    fn invoke_switch(
+        &self,
+        project: &str,
+        tags: &[String],
+        message: &Option<String>,
+        track: &TrackArgs,
+    ) -> TrackerResult {
+        log::info!("switch to {} executed", project);
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::time_data(&settings, &time, TimeData::read_from_file)?;
+        time_data.append_project(
+            Status::Connect,
+            time,
+            Some(project.to_owned()),
+            tags.to_vec(),
+        )?;
+        if let Some(m) = message.as_ref() {
+            time_data.note(m.to_owned())?;
+        }
+        time_data.write_to_file()
+    }
+
+    fn invoke_break(&self, track: &TrackArgs) -> TrackerResult {
         log::info!("break executed");
-        let settings = Settings::new()?;
-        let mut time_data = TimeData::builder()
-            .folder(settings.folder.into())
-            .today()
-            .build()?;
-        let now = Local::now();
-        time_data
-            .read_from_file()?
-            .append(Status::Break, now.to_utc())?
-            .write_to_file()
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::time_data(&settings, &time, TimeData::read_from_file)?;
+        time_data.append(Status::Break, time)?.write_to_file()
     }
 
-    fn invoke_end(&self) -> TrackerResult {
+    fn invoke_end(&self, track: &TrackArgs) -> TrackerResult {
         log::info!("end executed");
-        let settings = Settings::new()?;
-        let folder: &str = settings.folder.as_ref();
-        let mut time_data = TimeData::builder().folder(folder.into()).today().build()?;
-        time_data.read_from_file()?;
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::time_data(&settings, &time, TimeData::read_from_file)?;
+        time_data.append(Status::End, time)?;
         let status = StatusDaily::builder()
             .data(time_data.clone())
-            .settings(settings)
+            .settings(settings.clone())
             .build()?;
-        let now = Local::now();
         time_data
-            .append(Status::End, now.to_utc())?
             .assert_break(
                 status.exp_break.unwrap().duration,
                 status.r#break.unwrap().duration,
             )?
             .write_to_file()?;
-        self.invoke_status(&None, &false)
+        Cli::print_time_data(settings, time_data)
     }
 
-    fn invoke_disconnect(&self) -> TrackerResult {
+    fn invoke_disconnect(&self, track: &TrackArgs) -> TrackerResult {
         log::info!("disconnect executed");
-        let settings = Settings::new()?;
-        let mut time_data = TimeData::builder()
-            .folder(settings.folder.into())
-            .today()
-            .build()?;
-        let now = Local::now();
-        time_data
-            .read_from_file()?
-            .append(Status::Disconnect, now.to_utc())?
-            .write_to_file()
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::time_data(&settings, &time, TimeData::read_from_file)?;
+        time_data.append(Status::Disconnect, time)?.write_to_file()
     }
 
-    fn invoke_status(&self, week: &Option<i8>, table: &bool) -> TrackerResult {
+    fn invoke_status(
+        &self,
+        week: &Option<i8>,
+        month: &Option<i8>,
+        table: &bool,
+        output: &OutputFormat,
+    ) -> TrackerResult {
         log::info!("status executed");
-        let settings = Settings::new()?;
+        let settings = self.settings()?;
+
+        if let Some(m) = month {
+            let now = settings.timezone.today();
+            let time_data = TimeDataMonthly::builder()
+                .folder(settings.folder.to_owned().into())
+                .year(now.year().try_into()?)
+                .month(m, now.month())
+                .build()?;
+
+            let status = StatusMonthly::builder()
+                .data(time_data)
+                .settings(settings)
+                .build()?;
+
+            if *table {
+                status.format_table(&mut io::stdout())?;
+            } else {
+                println!("{}", status);
+            }
+            return Ok(());
+        }
 
         match week {
             Some(w) => {
-                let year = Local::now().year();
-                let cur_week: IsoWeek = Local::now().iso_week();
                 let time_data = TimeDataWeekly::builder()
                     .folder(settings.folder.to_owned().into())
-                    .year(year.try_into()?)
-                    .week(w, cur_week)
+                    .current_week(w, &settings.timezone)
                     .build()?;
 
                 let status = StatusWeekly::builder()
@@ -227,31 +691,318 @@ impl Cli {
                     .settings(settings)
                     .build()?;
 
-                if *table {
-                    status.format_table();
+                if let Some(o) = output.serialize(&status.output())? {
+                    println!("{}", o);
+                } else if *table {
+                    status.format_table(&mut io::stdout())?;
                 } else {
                     println!("{}", status);
                 }
+                Ok(())
+            }
+            None if output != &OutputFormat::Text => {
+                let status = Cli::status_daily_output(settings, &Utc::now())?;
+                if let Some(o) = output.serialize(&status)? {
+                    println!("{}", o);
+                }
+                Ok(())
             }
             None => {
-                let mut time_data = TimeData::builder()
-                    .folder(settings.folder.to_owned().into())
-                    .today()
-                    .build()?;
-                time_data.read_from_file()?;
-                let status = StatusDaily::builder()
-                    .data(time_data)
-                    .settings(settings)
-                    .build()?;
-                println!("{}", status);
+                self.print_status_daily(settings.clone(), &Utc::now())?;
+                // the balance is additional information and must not break the status
+                let yesterday = settings.timezone.today().pred_opt().unwrap();
+                match Balance::builder().settings(settings).until(yesterday).build() {
+                    Ok(balance) => println!("{}", balance.summary()),
+                    Err(e) => log::warn!("balance not available: {}", e),
+                }
+                Ok(())
             }
         }
+    }
+
+    fn invoke_balance(&self, until: &str) -> TrackerResult {
+        log::info!("balance executed");
+        let settings = self.settings()?;
+        let until = TimeExpression::day(until, settings.timezone.today())?;
+        let balance = Balance::builder().settings(settings).until(until).build()?;
+        println!("{}", balance);
+        Ok(())
+    }
+
+    fn invoke_report(&self, from: &str, to: &str, table: &bool, combined: &bool) -> TrackerResult {
+        log::info!("report executed");
+        let settings = self.settings()?;
+        if *combined {
+            return self.print_combined_report(settings, from, to);
+        }
+        let status = Cli::status_range(settings, from, to)?;
+
+        if *table {
+            status.format_table(&mut io::stdout())?;
+        } else {
+            println!("{}", status);
+        }
+        Ok(())
+    }
+
+    fn status_range(settings: Settings, from: &str, to: &str) -> Result<StatusRange, TrackerError> {
+        let today = settings.timezone.today();
+        let time_data = TimeDataRange::builder()
+            .folder(settings.folder.to_owned().into())
+            .from(TimeExpression::day(from, today)?)
+            .to(TimeExpression::day(to, today)?)
+            .build()?;
+
+        StatusRange::builder()
+            .data(time_data)
+            .settings(settings)
+            .build()
+    }
+
+    /// Report of every profile followed by their sum.
+    fn print_combined_report(&self, settings: Settings, from: &str, to: &str) -> TrackerResult {
+        if settings.profiles.is_empty() {
+            return Err(TrackerError::StatusRangeError {
+                message: "no profiles are configured".to_owned(),
+            });
+        }
+        let mut ranges = Vec::new();
+        for name in settings.profiles.keys() {
+            let status = Cli::status_range(self.profile_settings(Some(name))?, from, to)?;
+            println!("Profile {}\n{}", name, status);
+            ranges.push(status);
+        }
+        println!("Combined\n{}", StatusRange::sum(&ranges));
+        Ok(())
+    }
+
+    fn invoke_export(&self, args: &ExportArgs) -> TrackerResult {
+        log::info!("export executed");
+        let settings = self.settings()?;
+        let today = settings.timezone.today();
+        let time_data = TimeDataRange::builder()
+            .folder(settings.folder.to_owned().into())
+            .from(TimeExpression::day(&args.from, today)?)
+            .to(TimeExpression::day(&args.to, today)?)
+            .build()?;
+
+        let export = Export::builder()
+            .data(time_data)
+            .settings(settings)
+            .mode(args.mode)
+            .columns(args.columns.to_vec())
+            .breaks(args.breaks)
+            .build()?;
+
+        let content = match args.format {
+            ExportFormat::Csv => {
+                let delimiter = u8::try_from(args.delimiter).map_err(|_| TrackerError::ExportError {
+                    message: format!("delimiter {} is not a single byte character", args.delimiter),
+                })?;
+                export.csv(delimiter)?
+            }
+            ExportFormat::Ics => export.ics(),
+        };
+        match &args.output {
+            Some(file) => {
+                log::debug!("write export to {:?}", file);
+                std::fs::write(file, content)?;
+            }
+            None => print!("{}", content),
+        }
+        Ok(())
+    }
+
+    fn invoke_import(&self, format: &ImportFormat, file: &Path, dry_run: &bool) -> TrackerResult {
+        log::info!("import executed");
+        let settings = self.settings()?;
+        let mut import = Import::builder()
+            .folder(settings.folder.to_owned().into())
+            .file(file.to_path_buf())
+            .format(*format)
+            .timezone(settings.timezone)
+            .build()?;
+        println!("{}", import);
+        if !*dry_run && !import.days.is_empty() {
+            import.apply()?;
+            println!("Imported {} days.", import.days.len());
+        }
+        Ok(())
+    }
+
+    fn print_status_daily(&self, settings: Settings, time: &DateTime<Utc>) -> TrackerResult {
+        let time_data = Cli::time_data(&settings, time, TimeData::read_only)?;
+        Cli::print_time_data(settings, time_data)
+    }
+
+    /// Daily status for scripts, a day without entries only has its expected work time.
+    fn status_daily_output(settings: Settings, time: &DateTime<Utc>) -> Result<StatusDailyOutput, TrackerError> {
+        let time_data = Cli::time_data(&settings, time, TimeData::read_only)?;
+        if !time_data.entries.data.is_empty() {
+            return Ok(StatusDaily::builder().data(time_data).settings(settings).build()?.output());
+        }
+        let expected = i64::from(time_data.expected(&settings));
+        Ok(StatusDailyOutput {
+            date: time_data.date.map(|d| d.date_naive()),
+            absence: time_data.absence,
+            expected,
+            overtime: -expected,
+            ..Default::default()
+        })
+    }
+
+    fn print_time_data(settings: Settings, time_data: TimeData) -> TrackerResult {
+        if let (Some(a), true) = (time_data.absence, time_data.entries.data.is_empty()) {
+            println!("Absent today: {}", a);
+            return Ok(());
+        }
+        let status = StatusDaily::builder()
+            .data(time_data.to_owned())
+            .settings(settings.to_owned())
+            .build()?;
+        println!("{}", status);
+        Cli::print_rules(settings, time_data)
+    }
+
+    /// Findings of the labor rules, nothing is printed while all rules are met.
+    fn print_rules(settings: Settings, time_data: TimeData) -> TrackerResult {
+        let mut check = RuleCheck::builder();
+        if let (true, Some(date)) = (settings.rules.min_rest > 0, time_data.date) {
+            let mut previous = TimeData::builder()
+                .folder(settings.folder.to_owned().into())
+                .day(date.date_naive().pred_opt().unwrap())
+                .build()?;
+            previous.read_only()?;
+            check.previous(previous);
+        }
+        let check = check.data(time_data).settings(settings).build()?;
+        if !check.findings.is_empty() {
+            println!("\n{}", check);
+        }
+        Ok(())
+    }
+
+    fn invoke_absence(&self, action: &AbsenceCommands) -> TrackerResult {
+        log::info!("absence executed");
+        let settings = self.settings()?;
+        let today = settings.timezone.today();
+        let mut absences = Absences::builder()
+            .folder(settings.folder.to_owned().into())
+            .build()?;
+
+        let range = |from: &str, to: &Option<String>| -> Result<_, TrackerError> {
+            let from = TimeExpression::day(from, today)?;
+            let to = match to {
+                Some(t) => TimeExpression::day(t, today)?,
+                None => from,
+            };
+            Ok((from, to))
+        };
+
+        let year = match action {
+            AbsenceCommands::Add { r#type, from, to } => {
+                let (from, to) = range(from, to)?;
+                absences
+                    .add(from, to, *r#type, &settings.workperday)?
+                    .write_to_file()?;
+                from.year()
+            }
+            AbsenceCommands::Remove { from, to } => {
+                let (from, to) = range(from, to)?;
+                absences.remove(from, to)?.write_to_file()?;
+                from.year()
+            }
+            AbsenceCommands::List { year } => year.unwrap_or(today.year()),
+        };
+
+        let mut listed = absences.clone();
+        listed.data.retain(|d, _| d.year() == year);
+        println!("{}\n", listed);
+
+        let taken = absences.vacation_days(year);
+        match settings.absence.vacation {
+            0 => println!("Vacation {}: {} days taken", year, taken),
+            quota => println!(
+                "Vacation {}: {} of {} days taken, {} remaining",
+                year,
+                taken,
+                quota,
+                i64::from(quota) - taken as i64
+            ),
+        }
+        Ok(())
+    }
+
+    fn invoke_migrate(&self, dry_run: &bool) -> TrackerResult {
+        log::info!("migrate executed");
+        let settings = self.settings()?;
+        let mut migration = Migration::builder()
+            .folder(settings.folder.to_owned().into())
+            .timezone(settings.timezone)
+            .build()?;
+        println!("{}", migration);
+        if !*dry_run && !migration.moves.is_empty() {
+            migration.apply()?;
+            println!("Moved {} entries.", migration.moves.len());
+        }
+        Ok(())
+    }
+
+    fn invoke_edit(&self, day: &str, action: &EditCommands) -> TrackerResult {
+        log::info!("edit executed");
+        let settings = self.settings()?;
+        let now = Utc::now();
+        let date = TimeExpression::day(day, settings.timezone.today())?;
+        let mut time_data = TimeData::builder()
+            .folder(settings.folder.to_owned().into())
+            .timezone(settings.timezone)
+            .day(date)
+            .build()?;
+        time_data.read_from_file()?;
+
+        // times are relative to the edited day, but keep relative offsets to now
+        let resolve = |t: &str| -> Result<DateTime<Utc>, TrackerError> {
+            match TimeExpression::from_str(t)? {
+                TimeExpression::Time { days: 0, time: tm } => {
+                    TimeExpression::DateTime(date.and_time(tm)).resolve(now, &settings.timezone)
+                }
+                e => e.resolve(now, &settings.timezone),
+            }
+        };
+
+        match action {
+            EditCommands::List => {
+                println!("{}", time_data);
+                return Ok(());
+            }
+            EditCommands::Set { id, status, time } => {
+                let time = match time {
+                    Some(t) => Some(resolve(t)?),
+                    None => None,
+                };
+                time_data.set(*id, status.to_owned(), time)?.write_to_file()?;
+            }
+            EditCommands::Delete { id } => {
+                time_data.delete(*id)?.write_to_file()?;
+            }
+            EditCommands::Insert { status, time } => {
+                let time = resolve(time)?;
+                time_data.append(status.to_owned(), time)?.write_to_file()?;
+            }
+        }
+
+        println!("{}\n", time_data);
+        match StatusDaily::builder().data(time_data).settings(settings).build() {
+            Ok(status) => println!("{}", status),
+            Err(e) => log::warn!("status not available: {}", e),
+        }
         Ok(())
     }
 
     fn invoke_config(&self, edit: &bool) -> TrackerResult {
         log::info!("status executed");
-        let settings = Settings::new()?;
+        // not checked, so an invalid configuration can be fixed
+        let settings = Settings::read(&SettingsPaths::resolve(self.config.to_owned())?)?;
 
         if *edit {
             log::debug!("invoke default editor with config");
@@ -262,24 +1013,39 @@ impl Cli {
         Ok(())
     }
 
-    fn invoke_takeover(&self, minutes: &u16) -> TrackerResult {
+    fn invoke_config_check(&self) -> TrackerResult {
+        log::info!("config check executed");
+        let settings = Settings::read(&SettingsPaths::resolve(self.config.to_owned())?)?;
+        let check = SettingsCheck::from(&settings);
+        if check.problems.is_empty() {
+            println!("{} is valid", settings.file);
+        } else {
+            println!("{}", check);
+        }
+        if check.has_errors() {
+            return Err(TrackerError::TrackerConfigError(ConfigError::Message(format!("invalid {}", settings.file))));
+        }
+        Ok(())
+    }
+
+    fn invoke_takeover(&self, minutes: &u16, track: &TrackArgs) -> TrackerResult {
         log::info!("takeover {} minutes", minutes);
-        let settings = Settings::new()?;
-        let folder: &str = settings.folder.as_ref();
-        let mut time_data = TimeData::builder().folder(folder.into()).today().build()?;
-        time_data.read_from_file()?;
+        let settings = self.settings()?;
+        let time = track.time(&settings.timezone)?;
+        let mut time_data = Cli::time_data(&settings, &time, TimeData::read_from_file)?;
+        time_data.append(Status::End, time)?;
         let status = StatusDaily::builder()
             .data(time_data.clone())
-            .settings(settings)
+            .settings(settings.clone())
             .build()?;
-        let now = Local::now();
         time_data
-            .append(Status::End, now.to_utc())?
             .assert_break(
                 status.exp_break.unwrap().duration,
                 status.r#break.unwrap().duration,
             )?
+            .takeover(Duration::minutes((*minutes).into()))?
             .write_to_file()?;
-        self.invoke_status(&None, &false)
+        Takeover::builder().file(settings.takeover_file()).set(*minutes)?;
+        Cli::print_time_data(settings, time_data)
     }
 }
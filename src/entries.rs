@@ -1,18 +1,24 @@
+use std::collections::BTreeMap;
+
+use chrono::{ DateTime, Duration, Utc };
 use serde::{ Deserialize, Serialize };
 
-use crate::Entry;
+use crate::{ Entry, Status };
 
-const CURRENT_VERSION: u8 = 1;
+pub(crate) const CURRENT_VERSION: u8 = 3;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Entries {
     pub data: Vec<Entry>,
     pub version: u8,
+    /// Note of the whole day.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
 }
 
 impl Default for Entries {
     fn default() -> Self {
-        Self { data: Default::default(), version: CURRENT_VERSION }
+        Self { data: Default::default(), version: CURRENT_VERSION, note: None }
     }
 }
 
@@ -25,4 +31,70 @@ impl Entries {
         self.data.append(entry);
         self
     }
+
+    /// Time of every connect segment per project, open segments last until `end`.
+    pub fn projects(&self, end: DateTime<Utc>) -> BTreeMap<Option<String>, Duration> {
+        let mut projects: BTreeMap<Option<String>, Duration> = BTreeMap::new();
+        let mut project: Option<String> = None;
+        for (n, e) in self.data.iter().enumerate() {
+            if e.status != Status::Connect {
+                continue;
+            }
+            if e.project.is_some() {
+                project = e.project.to_owned();
+            }
+            let until = self.data.get(n + 1).map_or(end, |x| x.time);
+            if until > e.time {
+                *projects.entry(project.to_owned()).or_insert_with(Duration::zero) += until - e.time;
+            }
+        }
+        projects
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn entry(status: Status, hour: u32, project: Option<&str>) -> Entry {
+        Entry {
+            status,
+            time: Utc.with_ymd_and_hms(2022, 3, 1, hour, 0, 0).unwrap(),
+            project: project.map(|p| p.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_sum_projects() {
+        let entries = Entries {
+            data: [
+                entry(Status::Connect, 8, None),
+                entry(Status::Connect, 9, Some("foo")),
+                entry(Status::Break, 11, None),
+                entry(Status::Connect, 12, None),
+                entry(Status::Connect, 14, Some("bar")),
+                entry(Status::End, 16, None),
+            ].to_vec(),
+            ..Default::default()
+        };
+        let projects = entries.projects(Utc::now());
+
+        assert_eq!(3, projects.len());
+        assert_eq!(Some(&Duration::hours(1)), projects.get(&None));
+        assert_eq!(Some(&Duration::hours(4)), projects.get(&Some("foo".to_owned())));
+        assert_eq!(Some(&Duration::hours(2)), projects.get(&Some("bar".to_owned())));
+    }
+
+    #[test]
+    fn should_end_open_segment() {
+        let entries = Entries {
+            data: [entry(Status::Connect, 8, Some("foo"))].to_vec(),
+            ..Default::default()
+        };
+        let projects = entries.projects(Utc.with_ymd_and_hms(2022, 3, 1, 10, 30, 0).unwrap());
+        assert_eq!(Some(&Duration::minutes(150)), projects.get(&Some("foo".to_owned())));
+    }
 }
@@ -12,6 +12,16 @@ pub struct Entry {
     pub(crate) status: Status,
 
     pub(crate) time: DateTime<Utc>,
+
+    /// Project of a connect segment, later connects without project continue it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) project: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) tags: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) note: Option<String>,
 }
 
 impl Default for Entry {
@@ -20,11 +30,14 @@ impl Default for Entry {
             id: 0,
             status: Status::Connect,
             time: DateTime::default(),
+            project: None,
+            tags: Vec::new(),
+            note: None,
         }
     }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, clap::ValueEnum)]
 pub enum Status {
     Connect,
     Disconnect,
@@ -51,9 +64,9 @@ impl FromStr for Entry {
     }
 }
 
-impl ToString for Entry {
-    fn to_string(&self) -> String {
-        serde_json::to_string(&self).unwrap()
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", serde_json::to_string(&self).unwrap())
     }
 }
 
@@ -93,6 +106,13 @@ impl EntryBuilder {
         self.time_set = true;
         self
     }
+
+    /// Set project and tags of entry.
+    pub fn project(&mut self, project: Option<String>, tags: Vec<String>) -> &mut Self {
+        self.inner.project = project;
+        self.inner.tags = tags;
+        self
+    }
 }
 
 #[cfg(test)]
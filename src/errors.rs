@@ -11,7 +11,7 @@ pub enum TrackerError {
     ConverstionError(#[from] std::convert::Infallible),
     #[error("io error")]
     IoError(#[from] std::io::Error),
-    #[error("configuration error")]
+    #[error("configuration error: {0}")]
     TrackerConfigError(#[from] ConfigError),
     #[error("week number conversion")]
     TrackerIntError(#[from] std::num::TryFromIntError),
@@ -27,10 +27,32 @@ pub enum TrackerError {
     StatusError { message: String },
     #[error("weekly status error: {message}")]
     StatusWeeklyError { message: String },
+    #[error("monthly status error: {message}")]
+    StatusMonthlyError { message: String },
+    #[error("range status error: {message}")]
+    StatusRangeError { message: String },
     #[error("set takeover error: {message}")]
     TakeoverSetError { message: String },
     #[error("get takeover error: {message}")]
     TakeoverGetError { message: String },
+    #[error("time expression error: {message}")]
+    TimeExpressionError { message: String },
+    #[error("entry order error: {message}")]
+    EntryOrderError { message: String },
+    #[error("balance error: {message}")]
+    BalanceError { message: String },
+    #[error("absence error: {message}")]
+    AbsenceError { message: String },
+    #[error("holiday calendar error: {message}")]
+    HolidayCalendarError { message: String },
+    #[error("lock error: {message}")]
+    LockError { message: String },
+    #[error("migration error: {message}")]
+    MigrationError { message: String },
+    #[error("export error: {message}")]
+    ExportError { message: String },
+    #[error("import error: {message}")]
+    ImportError { message: String },
     // own
     #[error("file upgrade error")]
     UpgradeError(#[from] crate::models::UpgradeError)
@@ -0,0 +1,452 @@
+use chrono::{ DateTime, Utc };
+
+use crate::{ Entry, Settings, Status, StatusRangeBuilder, TimeData, TimeDataRange, TrackerError };
+
+/// Columns of an entry export, the first four are exported by default.
+pub const ENTRY_COLUMNS: [&str; 7] = ["date", "id", "status", "time", "project", "tags", "note"];
+
+/// Columns of a daily export, the first six are exported by default.
+pub const DAY_COLUMNS: [&str; 11] = [
+    "date",
+    "start",
+    "end",
+    "break",
+    "worktime",
+    "overtime",
+    "expected",
+    "notes",
+    "rounded_start",
+    "rounded_end",
+    "rounded_worktime",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    /// iCalendar events of work segments.
+    Ics,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportMode {
+    /// One row per entry.
+    #[default]
+    Entries,
+    /// One row per day with its daily status.
+    Days,
+}
+
+/// Rows of time data prepared for spreadsheets and segments for calendars.
+#[derive(Clone, Debug, Default)]
+pub struct Export {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+    pub events: Vec<ExportEvent>,
+}
+
+/// Work or break segment between an entry and the following one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportEvent {
+    /// Derived from the day and the id of the starting entry, so a new export updates the event.
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl Export {
+    pub fn builder() -> ExportBuilder {
+        ExportBuilder::default()
+    }
+
+    pub fn csv(&self, delimiter: u8) -> Result<String, TrackerError> {
+        let error = |e: csv::Error| TrackerError::ExportError { message: e.to_string() };
+        let mut w = csv::WriterBuilder::new().delimiter(delimiter).from_writer(vec![]);
+        w.write_record(&self.columns).map_err(error)?;
+        for r in self.rows.iter() {
+            w.write_record(r).map_err(error)?;
+        }
+        let content = w.into_inner().map_err(|e| TrackerError::ExportError { message: e.to_string() })?;
+        String::from_utf8(content).map_err(|e| TrackerError::ExportError { message: e.to_string() })
+    }
+
+    /// Events as iCalendar with CRLF line endings.
+    pub fn ics(&self) -> String {
+        let stamp = Export::ics_time(Utc::now());
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_owned(),
+            "VERSION:2.0".to_owned(),
+            "PRODID:-//trackrs//trackrs//EN".to_owned()
+        ];
+        for e in self.events.iter() {
+            lines.push("BEGIN:VEVENT".to_owned());
+            lines.push(format!("UID:{}", e.uid));
+            lines.push(format!("DTSTAMP:{}", stamp));
+            lines.push(format!("DTSTART:{}", Export::ics_time(e.start)));
+            lines.push(format!("DTEND:{}", Export::ics_time(e.end)));
+            lines.push(format!("SUMMARY:{}", Export::ics_text(&e.summary)));
+            if let Some(d) = e.description.as_ref() {
+                lines.push(format!("DESCRIPTION:{}", Export::ics_text(d)));
+            }
+            lines.push("END:VEVENT".to_owned());
+        }
+        lines.push("END:VCALENDAR".to_owned());
+        lines
+            .iter()
+            .map(|l| Export::ics_fold(l) + "\r\n")
+            .collect()
+    }
+
+    fn ics_time(time: DateTime<Utc>) -> String {
+        time.format("%Y%m%dT%H%M%SZ").to_string()
+    }
+
+    fn ics_text(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace(';', "\\;")
+            .replace(',', "\\,")
+            .replace('\n', "\\n")
+    }
+
+    /// Lines are limited to 75 octets, continuation lines start with a space.
+    fn ics_fold(line: &str) -> String {
+        let mut folded = String::new();
+        let mut length = 0;
+        for c in line.chars() {
+            if length + c.len_utf8() > 75 {
+                folded.push_str("\r\n ");
+                length = 1;
+            }
+            folded.push(c);
+            length += c.len_utf8();
+        }
+        folded
+    }
+}
+
+#[derive(Default)]
+pub struct ExportBuilder {
+    data: Option<TimeDataRange>,
+    settings: Option<Settings>,
+    mode: ExportMode,
+    columns: Vec<String>,
+    breaks: bool,
+}
+
+impl ExportBuilder {
+    pub fn data(&mut self, data: TimeDataRange) -> &mut Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn settings(&mut self, settings: Settings) -> &mut Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    pub fn mode(&mut self, mode: ExportMode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Columns in order of the export, the default columns of the mode when empty.
+    pub fn columns(&mut self, columns: Vec<String>) -> &mut Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Add breaks as separate events.
+    pub fn breaks(&mut self, breaks: bool) -> &mut Self {
+        self.breaks = breaks;
+        self
+    }
+
+    pub fn build(&self) -> Result<Export, TrackerError> {
+        let (data, settings) = match (self.data.as_ref(), self.settings.as_ref()) {
+            (Some(d), Some(s)) => (d, s),
+            _ => {
+                return Err(TrackerError::ExportError {
+                    message: "data and settings are required".to_owned(),
+                });
+            }
+        };
+        let columns = self.assert_columns()?;
+
+        let mut rows = Vec::new();
+        let mut events = Vec::new();
+        for t in data.entries.iter() {
+            events.append(&mut self.events(t));
+            match self.mode {
+                ExportMode::Entries => {
+                    for e in t.entries.data.iter() {
+                        rows.push(
+                            columns
+                                .iter()
+                                .map(|c| ExportBuilder::entry_value(c, t, e, settings))
+                                .collect()
+                        );
+                    }
+                }
+                ExportMode::Days => {
+                    rows.push(ExportBuilder::day_values(&columns, t, settings)?);
+                }
+            }
+        }
+        log::debug!("export {} rows with {:?}", rows.len(), columns);
+        Ok(Export { columns, rows, events })
+    }
+
+    /// Segments from each connect, and optionally each break, until the next entry.
+    fn events(&self, data: &TimeData) -> Vec<ExportEvent> {
+        let day = data.date.map(|d| d.format("%Y%m%d").to_string()).unwrap_or_default();
+        data.entries.data
+            .windows(2)
+            .filter_map(|w| {
+                let (e, next) = (&w[0], &w[1]);
+                let summary = match (&e.status, e.project.as_ref()) {
+                    (Status::Connect, Some(p)) => format!("Work: {}", p),
+                    (Status::Connect, None) => "Work".to_owned(),
+                    (Status::Break, _) if self.breaks => "Break".to_owned(),
+                    _ => {
+                        return None;
+                    }
+                };
+                let description = e.note
+                    .iter()
+                    .cloned()
+                    .chain(e.tags.iter().map(|t| format!("#{}", t)))
+                    .collect::<Vec<String>>();
+                Some(ExportEvent {
+                    uid: format!("{}-{}@trackrs", day, e.id),
+                    summary,
+                    description: (!description.is_empty()).then(|| description.join(" ")),
+                    start: e.time,
+                    end: next.time,
+                })
+            })
+            .collect()
+    }
+
+    fn assert_columns(&self) -> Result<Vec<String>, TrackerError> {
+        let (available, default): (&[&str], usize) = match self.mode {
+            ExportMode::Entries => (&ENTRY_COLUMNS, 4),
+            ExportMode::Days => (&DAY_COLUMNS, 6),
+        };
+        if self.columns.is_empty() {
+            return Ok(available[..default].iter().map(|c| c.to_string()).collect());
+        }
+        match self.columns.iter().find(|c| !available.contains(&c.as_str())) {
+            Some(c) =>
+                Err(TrackerError::ExportError {
+                    message: format!("unknown column {}, available are {}", c, available.join(", ")),
+                }),
+            None => Ok(self.columns.to_owned()),
+        }
+    }
+
+    fn entry_value(column: &str, data: &TimeData, e: &Entry, settings: &Settings) -> String {
+        match column {
+            "date" => ExportBuilder::date(data),
+            "id" => e.id.to_string(),
+            "status" => format!("{:?}", e.status),
+            "time" => ExportBuilder::time(e.time, settings),
+            "project" => e.project.to_owned().unwrap_or_default(),
+            "tags" => e.tags.join(" "),
+            "note" => e.note.to_owned().unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    fn day_values(columns: &[String], data: &TimeData, settings: &Settings) -> Result<Vec<String>, TrackerError> {
+        let (s, expected) = StatusRangeBuilder::daily(data, settings)?;
+        Ok(
+            columns
+                .iter()
+                .map(|c| match c.as_str() {
+                    "date" => ExportBuilder::date(data),
+                    "start" => s.start.as_ref().map(|t| t.clock()).unwrap_or_default(),
+                    "end" => s.end.as_ref().map(|t| t.clock()).unwrap_or_default(),
+                    "break" => s.r#break.as_ref().map(|t| t.to_string()).unwrap_or_default(),
+                    "worktime" => s.worktime.to_string(),
+                    "overtime" => s.overtime.to_string(),
+                    "expected" => expected.to_string(),
+                    "notes" => s.notes_line(),
+                    "rounded_start" => s.rounded_start.as_ref().map(|t| t.clock()).unwrap_or_default(),
+                    "rounded_end" => s.rounded_end.as_ref().map(|t| t.clock()).unwrap_or_default(),
+                    "rounded_worktime" => s.rounded_worktime.to_string(),
+                    _ => String::new(),
+                })
+                .collect()
+        )
+    }
+
+    fn date(data: &TimeData) -> String {
+        data.date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default()
+    }
+
+    fn time(time: DateTime<Utc>, settings: &Settings) -> String {
+        settings.timezone.local(time).format("%Y-%m-%d %H:%M:%S").to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{ NaiveDate, TimeZone };
+
+    use crate::{ Entries, Rounding, RoundingMode, RoundingSettings };
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn data() -> TimeDataRange {
+        let time = |hour: u32| Utc.with_ymd_and_hms(2022, 2, 2, hour, 0, 0).unwrap();
+        let entry = |id: u8, status: Status, hour: u32| Entry { id, status, time: time(hour), ..Default::default() };
+        TimeDataRange {
+            entries: vec![
+                TimeData {
+                    date: Some(time(0)),
+                    entries: Entries {
+                        data: vec![
+                            Entry { project: Some("foo".to_owned()), ..entry(1, Status::Connect, 8) },
+                            entry(2, Status::Break, 12),
+                            Entry { note: Some("lunch, late".to_owned()), ..entry(3, Status::Connect, 13) },
+                            entry(4, Status::End, 17)
+                        ],
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                },
+                TimeData {
+                    date: Some(Utc.with_ymd_and_hms(2022, 2, 3, 0, 0, 0).unwrap()),
+                    ..Default::default()
+                }
+            ],
+            from: NaiveDate::from_ymd_opt(2022, 2, 2).unwrap(),
+            to: NaiveDate::from_ymd_opt(2022, 2, 3).unwrap(),
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            timezone: chrono_tz::UTC.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn should_export_entries() -> Result<(), TrackerError> {
+        logger();
+        let export = Export::builder().data(data()).settings(settings()).build()?;
+        assert_eq!(4, export.rows.len());
+        assert_eq!(
+            "date,id,status,time\n2022-02-02,1,Connect,2022-02-02 08:00:00\n",
+            export.csv(b',')?.lines().take(2).collect::<Vec<&str>>().join("\n") + "\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_selected_columns() -> Result<(), TrackerError> {
+        logger();
+        let export = Export::builder()
+            .data(data())
+            .settings(settings())
+            .columns(vec!["id".to_owned(), "project".to_owned(), "note".to_owned()])
+            .build()?;
+        let csv = export.csv(b';')?;
+        let lines = csv.lines().collect::<Vec<&str>>();
+        assert_eq!("id;project;note", lines[0]);
+        assert_eq!("1;foo;", lines[1]);
+        assert_eq!("3;;lunch, late", lines[3]);
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_days() -> Result<(), TrackerError> {
+        logger();
+        let export = Export::builder().data(data()).settings(settings()).mode(ExportMode::Days).build()?;
+        let csv = export.csv(b',')?;
+        let lines = csv.lines().collect::<Vec<&str>>();
+        assert_eq!("date,start,end,break,worktime,overtime", lines[0]);
+        assert_eq!("2022-02-02,08:00,17:00,01:00,08:00,00:00", lines[1]);
+        assert_eq!("2022-02-03,,,,00:00,-08:00", lines[2]);
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_rounded_days() -> Result<(), TrackerError> {
+        logger();
+        let settings = Settings {
+            rounding: RoundingSettings {
+                start: Rounding { minutes: 15, mode: RoundingMode::Up },
+                end: Rounding { minutes: 15, mode: RoundingMode::Down },
+                ..Default::default()
+            },
+            ..settings()
+        };
+        let mut data = data();
+        data.entries[0].entries.data[0].time += chrono::Duration::minutes(5);
+        let export = Export::builder()
+            .data(data)
+            .settings(settings)
+            .mode(ExportMode::Days)
+            .columns(["start", "rounded_start", "worktime", "rounded_worktime"].map(|c| c.to_owned()).to_vec())
+            .build()?;
+        assert_eq!(vec!["08:05", "08:15", "07:55", "07:45"], export.rows[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn should_export_segments() -> Result<(), TrackerError> {
+        logger();
+        let export = Export::builder().data(data()).settings(settings()).build()?;
+        assert_eq!(2, export.events.len());
+        assert_eq!("20220202-1@trackrs", export.events[0].uid);
+        assert_eq!("Work: foo", export.events[0].summary);
+        assert_eq!(Utc.with_ymd_and_hms(2022, 2, 2, 12, 0, 0).unwrap(), export.events[0].end);
+        assert_eq!(Some("lunch, late".to_owned()), export.events[1].description);
+
+        let export = Export::builder().data(data()).settings(settings()).breaks(true).build()?;
+        assert_eq!(3, export.events.len());
+        assert_eq!("20220202-2@trackrs", export.events[1].uid);
+        assert_eq!("Break", export.events[1].summary);
+        Ok(())
+    }
+
+    #[test]
+    fn should_write_ics() -> Result<(), TrackerError> {
+        logger();
+        let ics = Export::builder().data(data()).settings(settings()).build()?.ics();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.contains("UID:20220202-3@trackrs\r\nDTSTAMP:"));
+        assert!(ics.contains("DTSTART:20220202T130000Z\r\nDTEND:20220202T170000Z\r\n"));
+        assert!(ics.contains("DESCRIPTION:lunch\\, late\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        Ok(())
+    }
+
+    #[test]
+    fn should_fold_long_lines() {
+        let line = format!("SUMMARY:{}", "x".repeat(80));
+        let folded = Export::ics_fold(&line);
+        let lines = folded.split("\r\n").collect::<Vec<&str>>();
+        assert_eq!(75, lines[0].len());
+        assert_eq!(format!(" {}", "x".repeat(13)), lines[1]);
+    }
+
+    #[test]
+    fn unknown_column() {
+        let res = Export::builder()
+            .data(data())
+            .settings(settings())
+            .mode(ExportMode::Days)
+            .columns(vec!["status".to_owned()])
+            .build();
+        assert!(res.err().unwrap().to_string().starts_with("export error: unknown column status"));
+    }
+}
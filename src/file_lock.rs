@@ -0,0 +1,111 @@
+use std::{
+    fs::{ File, OpenOptions, TryLockError },
+    path::{ Path, PathBuf },
+    thread,
+    time::{ Duration, SystemTime },
+};
+
+use crate::TrackerError;
+
+const RETRY_AFTER: Duration = Duration::from_millis(50);
+
+/// Advisory lock of a file, taken on a lock file next to it.
+///
+/// The operating system releases the lock when the file is closed or its process ends, the lock file itself is
+/// kept, so every process locks the same file.
+#[derive(Debug)]
+pub(crate) struct FileLock {
+    file: PathBuf,
+    handle: File,
+}
+
+impl FileLock {
+    /// Take the lock of `target`, waiting at most `timeout` for another process to release it.
+    pub(crate) fn acquire(target: &Path, timeout: Duration) -> Result<FileLock, TrackerError> {
+        let mut name = target.as_os_str().to_owned();
+        name.push(".lock");
+        let file = PathBuf::from(name);
+        let handle = OpenOptions::new().write(true).create(true).truncate(false).open(&file)?;
+        let start = SystemTime::now();
+
+        loop {
+            match handle.try_lock() {
+                Ok(()) => {
+                    log::trace!("locked {:?}", &file);
+                    return Ok(FileLock { file, handle });
+                }
+                Err(TryLockError::WouldBlock) => {
+                    if start.elapsed().unwrap_or_default() >= timeout {
+                        return Err(TrackerError::LockError {
+                            message: format!("{} is in use by another trackrs process", target.display()),
+                        });
+                    }
+                    thread::sleep(RETRY_AFTER);
+                }
+                Err(TryLockError::Error(e)) => {
+                    return Err(e.into());
+                }
+            }
+        }
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        log::trace!("unlock {:?}", &self.file);
+        let _ = self.handle.unlock();
+    }
+}
+
+/// Lock owned by a single value, clones do not hold the lock.
+#[derive(Debug, Default)]
+pub(crate) struct LockHandle(pub(crate) Option<FileLock>);
+
+impl Clone for LockHandle {
+    fn clone(&self) -> Self {
+        LockHandle(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_lock_and_release() -> Result<(), TrackerError> {
+        let temp_dir = tempfile::tempdir()?;
+        let target = temp_dir.path().join("20220202.json");
+
+        let lock = FileLock::acquire(&target, Duration::from_millis(100))?;
+        assert!(temp_dir.path().join("20220202.json.lock").exists());
+
+        let res = FileLock::acquire(&target, Duration::from_millis(100));
+        assert!(res.err().unwrap().to_string().starts_with("lock error: "));
+
+        drop(lock);
+        FileLock::acquire(&target, Duration::from_millis(100))?;
+        Ok(())
+    }
+
+    #[test]
+    fn should_ignore_left_over_lock_file() -> Result<(), TrackerError> {
+        let temp_dir = tempfile::tempdir()?;
+        let target = temp_dir.path().join("20220202.json");
+
+        // eg. of a crashed process, only the lock on the file counts
+        std::fs::write(temp_dir.path().join("20220202.json.lock"), "12345")?;
+        FileLock::acquire(&target, Duration::from_millis(100))?;
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_clone_lock() -> Result<(), TrackerError> {
+        let temp_dir = tempfile::tempdir()?;
+        let target = temp_dir.path().join("20220202.json");
+
+        let handle = LockHandle(Some(FileLock::acquire(&target, Duration::from_millis(100))?));
+        drop(handle.clone());
+        assert!(FileLock::acquire(&target, Duration::from_millis(100)).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+use std::{ collections::BTreeMap, fs, path::PathBuf };
+
+use chrono::NaiveDate;
+use serde::Deserialize;
+
+use crate::TrackerError;
+
+/// Holidays of a local calendar file with the minutes of work expected on each day.
+#[derive(Default, Clone, Debug)]
+pub struct HolidayCalendar {
+    pub days: BTreeMap<NaiveDate, u16>,
+}
+
+/// Entry of a JSON calendar, either a plain date or a date with reduced work time.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum HolidayEntry {
+    Date(NaiveDate),
+    Reduced {
+        date: NaiveDate,
+        minutes: u16,
+    },
+}
+
+impl HolidayCalendar {
+    pub fn builder() -> HolidayCalendarBuilder {
+        HolidayCalendarBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct HolidayCalendarBuilder {
+    file: Option<PathBuf>,
+}
+
+impl HolidayCalendarBuilder {
+    pub fn file(&mut self, file: PathBuf) -> &mut Self {
+        log::debug!("set holiday calendar to: {:?}", &file);
+        self.file = Some(file);
+        self
+    }
+
+    /// Read the calendar, `.ics` files as iCalendar and any other file as JSON.
+    pub fn build(&self) -> Result<HolidayCalendar, TrackerError> {
+        let file = match self.file.to_owned() {
+            Some(f) => f,
+            None => {
+                return Err(TrackerError::HolidayCalendarError {
+                    message: "file not defined".to_owned(),
+                });
+            }
+        };
+        let content = match fs::read_to_string(&file) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(TrackerError::HolidayCalendarError {
+                    message: format!("{} could not be read: {}", file.display(), e),
+                });
+            }
+        };
+
+        let days = match file.extension().and_then(|e| e.to_str()) {
+            Some(e) if e.eq_ignore_ascii_case("ics") => HolidayCalendarBuilder::ics(&content)?,
+            _ => HolidayCalendarBuilder::json(&content)?,
+        };
+        log::debug!("read {} holidays from {:?}", days.len(), &file);
+        Ok(HolidayCalendar { days })
+    }
+
+    fn json(content: &str) -> Result<BTreeMap<NaiveDate, u16>, TrackerError> {
+        let entries: Vec<HolidayEntry> = serde_json::from_str(content)?;
+        Ok(
+            entries
+                .into_iter()
+                .map(|e| match e {
+                    HolidayEntry::Date(d) => (d, 0),
+                    HolidayEntry::Reduced { date, minutes } => (date, minutes),
+                })
+                .collect()
+        )
+    }
+
+    /// All-day events of an iCalendar file, an event spans until the exclusive `DTEND`.
+    ///
+    /// `X-TRACKRS-MINUTES` sets reduced work time for the event, recurring events are not expanded. Timed events are
+    /// skipped.
+    fn ics(content: &str) -> Result<BTreeMap<NaiveDate, u16>, TrackerError> {
+        // folded lines continue with a leading space or tab
+        let unfolded = content.replace("\r\n", "\n").replace("\n ", "").replace("\n\t", "");
+
+        let mut days = BTreeMap::new();
+        // start, end, minutes and whether the event lasts all day
+        let mut event: Option<(Option<NaiveDate>, Option<NaiveDate>, u16, bool)> = None;
+
+        for line in unfolded.lines() {
+            let Some((name, value)) = line.split_once(':') else {
+                continue;
+            };
+            let mut params = name.split(';');
+            let name = params.next().unwrap_or_default().to_ascii_uppercase();
+            match (name.as_str(), event.as_mut()) {
+                ("BEGIN", _) if value.eq_ignore_ascii_case("VEVENT") => {
+                    event = Some((None, None, 0, false));
+                }
+                ("DTSTART", Some(e)) => {
+                    e.0 = Some(HolidayCalendarBuilder::ics_date(value)?);
+                    e.3 = params.any(|p| p.eq_ignore_ascii_case("VALUE=DATE")) || value.trim().len() == 8;
+                }
+                ("DTEND", Some(e)) => {
+                    e.1 = Some(HolidayCalendarBuilder::ics_date(value)?);
+                }
+                ("X-TRACKRS-MINUTES", Some(e)) => {
+                    e.2 = value.trim().parse()?;
+                }
+                ("RRULE", Some(_)) => {
+                    log::warn!("recurring holiday is only applied once: {}", value);
+                }
+                ("END", Some((start, end, minutes, all_day))) if value.eq_ignore_ascii_case("VEVENT") => {
+                    let Some(start) = start.to_owned() else {
+                        return Err(TrackerError::HolidayCalendarError {
+                            message: "event without DTSTART".to_owned(),
+                        });
+                    };
+                    if !*all_day {
+                        log::info!("skip timed event on {}, it is no holiday", start);
+                        event = None;
+                        continue;
+                    }
+                    let end = end.unwrap_or(start.succ_opt().unwrap());
+                    start
+                        .iter_days()
+                        .take_while(|d| d < &end || d == &start)
+                        .for_each(|d| {
+                            days.insert(d, minutes.to_owned());
+                        });
+                    event = None;
+                }
+                _ => {}
+            }
+        }
+        Ok(days)
+    }
+
+    fn ics_date(value: &str) -> Result<NaiveDate, TrackerError> {
+        let date = value.trim().get(0..8).unwrap_or_default();
+        match NaiveDate::parse_from_str(date, "%Y%m%d") {
+            Ok(d) => Ok(d),
+            Err(_) => Err(TrackerError::HolidayCalendarError {
+                message: format!("date {} is not valid", value),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use indoc::indoc;
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn date(month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2022, month, day).unwrap()
+    }
+
+    fn write(dir: &tempfile::TempDir, name: &str, content: &str) -> Result<PathBuf, TrackerError> {
+        let path = dir.path().join(name);
+        let mut f = fs::File::create(&path)?;
+        f.write_all(content.as_bytes())?;
+        Ok(path)
+    }
+
+    mod builder {
+        use super::*;
+
+        #[test]
+        fn should_read_json() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let file = write(
+                &temp_dir,
+                "holidays.json",
+                r#"["2022-12-25", {"date": "2022-12-24", "minutes": 240}]"#
+            )?;
+
+            let c = HolidayCalendar::builder().file(file).build()?;
+            assert_eq!(2, c.days.len());
+            assert_eq!(Some(&0), c.days.get(&date(12, 25)));
+            assert_eq!(Some(&240), c.days.get(&date(12, 24)));
+            Ok(())
+        }
+
+        #[test]
+        fn should_read_ics() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let file = write(
+                &temp_dir,
+                "holidays.ics",
+                indoc! {"
+                    BEGIN:VCALENDAR\r
+                    VERSION:2.0\r
+                    BEGIN:VEVENT\r
+                    DTSTART;VALUE=DATE:20221003\r
+                    SUMMARY:Tag der Deutschen\r
+                      Einheit\r
+                    END:VEVENT\r
+                    BEGIN:VEVENT\r
+                    DTSTART;VALUE=DATE:20221226\r
+                    DTEND;VALUE=DATE:20221228\r
+                    END:VEVENT\r
+                    BEGIN:VEVENT\r
+                    DTSTART;VALUE=DATE:20221231\r
+                    X-TRACKRS-MINUTES:240\r
+                    END:VEVENT\r
+                    BEGIN:VEVENT\r
+                    DTSTART:20221224T090000Z\r
+                    DTEND:20221224T100000Z\r
+                    SUMMARY:Meeting\r
+                    END:VEVENT\r
+                    END:VCALENDAR\r
+                "}
+            )?;
+
+            let c = HolidayCalendar::builder().file(file).build()?;
+            assert_eq!(4, c.days.len());
+            assert_eq!(Some(&0), c.days.get(&date(10, 3)));
+            assert_eq!(Some(&0), c.days.get(&date(12, 27)));
+            assert_eq!(None, c.days.get(&date(12, 28)));
+            assert_eq!(Some(&240), c.days.get(&date(12, 31)));
+            assert_eq!(None, c.days.get(&date(12, 24)));
+            Ok(())
+        }
+
+        #[test]
+        fn missing_file() {
+            let res = HolidayCalendar::builder().file("/not/existing.ics".into()).build();
+            assert!(res.is_err());
+            assert!(res.err().unwrap().to_string().starts_with("holiday calendar error: /not/existing.ics"));
+        }
+
+        #[test]
+        fn invalid_ics_date() -> Result<(), TrackerError> {
+            let temp_dir = tempfile::tempdir()?;
+            let file = write(&temp_dir, "h.ics", "BEGIN:VEVENT\nDTSTART:2022\nEND:VEVENT\n")?;
+            let res = HolidayCalendar::builder().file(file).build();
+            assert_eq!("holiday calendar error: date 2022 is not valid", res.err().unwrap().to_string());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,544 @@
+use std::{ collections::BTreeMap, fmt::Display, fs, path::PathBuf };
+
+use chrono::{ DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc };
+
+use crate::{ Entry, Folder, Status, TimeData, TrackerError, WorkTimeZone };
+
+const DATE_FORMATS: [&str; 3] = ["%Y-%m-%d", "%m/%d/%Y", "%d.%m.%Y"];
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+const DATE_TIME_FORMATS: [&str; 3] = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    /// CSV report of Toggl or Clockify with start and end date and time columns.
+    #[value(alias = "clockify")]
+    Toggl,
+    /// Data file of Timewarrior, eg. 2022-02.data.
+    Timewarrior,
+    /// CSV with the columns start and end, optionally project, tags and note.
+    Csv,
+}
+
+/// Time files created from the records of another time tracker.
+#[derive(Default, Debug)]
+pub struct Import {
+    pub days: Vec<TimeData>,
+    /// Days which are not imported, because their time file already has entries.
+    pub conflicts: Vec<NaiveDate>,
+    pub skipped: Vec<ImportSkip>,
+    timezone: WorkTimeZone,
+}
+
+/// Line of the imported file which could not be mapped to entries.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ImportSkip {
+    pub line: u64,
+    pub reason: String,
+}
+
+/// Time span of another time tracker.
+#[derive(Clone, Debug, Default)]
+struct ImportRecord {
+    line: u64,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    project: Option<String>,
+    tags: Vec<String>,
+    note: Option<String>,
+}
+
+impl Import {
+    pub fn builder() -> ImportBuilder {
+        ImportBuilder::default()
+    }
+
+    /// Write the time files of all days without conflict.
+    pub fn apply(&mut self) -> Result<&mut Self, TrackerError> {
+        for time_data in self.days.iter_mut() {
+            log::info!("write imported time file {:?}", time_data.file);
+            time_data.write_to_file()?;
+        }
+        Ok(self)
+    }
+}
+
+impl Display for Import {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut lines = self.days
+            .iter()
+            .map(|t| {
+                let (first, last) = (t.entries.data.first(), t.entries.data.last());
+                format!(
+                    "{} {} - {} {:>3} entries",
+                    t.date.map(|d| d.format("%Y-%m-%d").to_string()).unwrap_or_default(),
+                    first.map(|e| self.timezone.local(e.time).format("%H:%M").to_string()).unwrap_or_default(),
+                    last.map(|e| self.timezone.local(e.time).format("%H:%M").to_string()).unwrap_or_default(),
+                    t.entries.data.len()
+                )
+            })
+            .collect::<Vec<String>>();
+        if lines.is_empty() {
+            lines.push("No days to import.".to_owned());
+        }
+        for d in self.conflicts.iter() {
+            lines.push(format!("{} not imported, the time file already has entries", d.format("%Y-%m-%d")));
+        }
+        for s in self.skipped.iter() {
+            lines.push(format!("Line {} not imported: {}", s.line, s.reason));
+        }
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[derive(Default)]
+pub struct ImportBuilder {
+    folder: Option<Folder>,
+    file: Option<PathBuf>,
+    format: Option<ImportFormat>,
+    timezone: WorkTimeZone,
+}
+
+impl ImportBuilder {
+    pub fn folder(&mut self, folder: Folder) -> &mut Self {
+        self.folder = Some(folder);
+        self
+    }
+
+    /// File exported by the other time tracker.
+    pub fn file(&mut self, file: PathBuf) -> &mut Self {
+        self.file = Some(file);
+        self
+    }
+
+    pub fn format(&mut self, format: ImportFormat) -> &mut Self {
+        self.format = Some(format);
+        self
+    }
+
+    /// Timezone of times without offset and of the days, the system timezone by default.
+    pub fn timezone(&mut self, timezone: WorkTimeZone) -> &mut Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Read the records and lock the time files of all days without conflict.
+    ///
+    /// Records are grouped by the day they start at, gaps between records of a day become breaks.
+    pub fn build(&self) -> Result<Import, TrackerError> {
+        let (folder, file, format) = match (self.folder.to_owned(), self.file.as_ref(), self.format) {
+            (Some(d), Some(f), Some(x)) => (d, f, x),
+            _ => {
+                return Err(TrackerError::ImportError {
+                    message: "folder, file and format are required".to_owned(),
+                });
+            }
+        };
+        // read as bytes, so a row with invalid characters only skips the row
+        let content = fs::read(file)?;
+        let (mut records, mut skipped) = match format {
+            ImportFormat::Toggl => self.toggl(&content)?,
+            ImportFormat::Timewarrior => self.timewarrior(&String::from_utf8_lossy(&content)),
+            ImportFormat::Csv => self.csv(&content)?,
+        };
+        records.sort_by_key(|r| r.start);
+
+        let mut days: BTreeMap<NaiveDate, Vec<Entry>> = BTreeMap::new();
+        let mut previous: Option<DateTime<Utc>> = None;
+        for r in records {
+            if previous.is_some_and(|p| r.start < p) {
+                skipped.push(ImportSkip { line: r.line, reason: "overlaps a previous record".to_owned() });
+                continue;
+            }
+            previous = Some(r.end);
+            let entries = days.entry(self.timezone.date(r.start)).or_default();
+            match entries.last_mut() {
+                // a record which directly follows another one switches the project
+                Some(e) if e.time == r.start => {
+                    e.status = Status::Connect;
+                }
+                Some(e) => {
+                    e.status = Status::Break;
+                    entries.push(Entry::builder().time(r.start).build()?);
+                }
+                None => entries.push(Entry::builder().time(r.start).build()?),
+            }
+            if let Some(e) = entries.last_mut() {
+                e.project = r.project;
+                e.tags = r.tags;
+                e.note = r.note;
+            }
+            entries.push(Entry::builder().status(Status::End).time(r.end).build()?);
+        }
+
+        let mut import = Import { timezone: self.timezone, skipped, ..Default::default() };
+        for (day, data) in days {
+            let mut time_data = TimeData::builder().folder(folder.to_owned()).day(day).build()?;
+            time_data.read_from_file()?;
+            if !time_data.entries.data.is_empty() {
+                log::debug!("time file of {} already has entries", day);
+                time_data.unlock();
+                import.conflicts.push(day);
+                continue;
+            }
+            time_data.entries.data = data;
+            time_data.renumber();
+            import.days.push(time_data);
+        }
+        import.skipped.sort_by_key(|s| s.line);
+        Ok(import)
+    }
+
+    fn toggl(&self, content: &[u8]) -> Result<(Vec<ImportRecord>, Vec<ImportSkip>), TrackerError> {
+        ImportBuilder::read_csv(content, |columns, row| {
+            let time = |date: &str, time: &str| -> Result<DateTime<Utc>, String> {
+                let (d, t) = (ImportBuilder::value(columns, row, date), ImportBuilder::value(columns, row, time));
+                let d = DATE_FORMATS.iter()
+                    .find_map(|f| NaiveDate::parse_from_str(&d, f).ok())
+                    .ok_or(format!("invalid {} '{}'", date, d))?;
+                let t = TIME_FORMATS.iter()
+                    .find_map(|f| NaiveTime::parse_from_str(&t, f).ok())
+                    .ok_or(format!("invalid {} '{}'", time, t))?;
+                self.utc(d.and_time(t))
+            };
+            let tags = ImportBuilder::value(columns, row, "tags")
+                .split(',')
+                .map(|t| t.trim().to_owned())
+                .filter(|t| !t.is_empty())
+                .collect();
+            Ok(ImportRecord {
+                start: time("start date", "start time")?,
+                end: time("end date", "end time")?,
+                project: ImportBuilder::optional(columns, row, "project"),
+                tags,
+                note: ImportBuilder::optional(columns, row, "description"),
+                ..Default::default()
+            })
+        }, &["start date", "start time", "end date", "end time"])
+    }
+
+    fn csv(&self, content: &[u8]) -> Result<(Vec<ImportRecord>, Vec<ImportSkip>), TrackerError> {
+        ImportBuilder::read_csv(content, |columns, row| {
+            let time = |column: &str| -> Result<DateTime<Utc>, String> {
+                let value = ImportBuilder::value(columns, row, column);
+                if let Ok(t) = DateTime::parse_from_rfc3339(&value) {
+                    return Ok(t.to_utc());
+                }
+                let t = DATE_TIME_FORMATS.iter()
+                    .find_map(|f| NaiveDateTime::parse_from_str(&value, f).ok())
+                    .ok_or(format!("invalid {} '{}'", column, value))?;
+                self.utc(t)
+            };
+            let tags = ImportBuilder::value(columns, row, "tags")
+                .split([',', ' '])
+                .filter(|t| !t.is_empty())
+                .map(|t| t.to_owned())
+                .collect();
+            Ok(ImportRecord {
+                start: time("start")?,
+                end: time("end")?,
+                project: ImportBuilder::optional(columns, row, "project"),
+                tags,
+                note: ImportBuilder::optional(columns, row, "note"),
+                ..Default::default()
+            })
+        }, &["start", "end"])
+    }
+
+    /// Intervals like `inc 20220202T080000Z - 20220202T120000Z # tag "other tag" # "annotation"`.
+    fn timewarrior(&self, content: &str) -> (Vec<ImportRecord>, Vec<ImportSkip>) {
+        let time = |value: &str| {
+            NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%SZ")
+                .map(|t| t.and_utc())
+                .map_err(|_| format!("invalid time '{}'", value))
+        };
+        let mut records = Vec::new();
+        let mut skipped = Vec::new();
+        for (i, l) in content.lines().enumerate() {
+            let line = (i + 1) as u64;
+            if l.trim().is_empty() {
+                continue;
+            }
+            let mut parts = l.splitn(3, " # ");
+            let interval = parts.next().unwrap_or_default().split_whitespace().collect::<Vec<&str>>();
+            let record = match interval[..] {
+                ["inc", start, "-", end] =>
+                    time(start).and_then(|s| time(end).map(|e| (s, e))).and_then(|(start, end)|
+                        ImportBuilder::assert_record(ImportRecord {
+                            line,
+                            start,
+                            end,
+                            tags: ImportBuilder::tags(parts.next().unwrap_or_default()),
+                            note: parts
+                                .next()
+                                .map(|n| n.trim().trim_matches('"').to_owned())
+                                .filter(|n| !n.is_empty()),
+                            ..Default::default()
+                        })
+                    ),
+                ["inc", _] => Err("interval is still open".to_owned()),
+                _ => Err("not an interval".to_owned()),
+            };
+            match record {
+                Ok(r) => records.push(r),
+                Err(reason) => skipped.push(ImportSkip { line, reason }),
+            }
+        }
+        (records, skipped)
+    }
+
+    /// Map each row by the lowercase column names, rows which can not be read or mapped are skipped.
+    fn read_csv<F>(
+        content: &[u8],
+        map: F,
+        required: &[&str]
+    ) -> Result<(Vec<ImportRecord>, Vec<ImportSkip>), TrackerError>
+        where F: Fn(&[String], &csv::StringRecord) -> Result<ImportRecord, String>
+    {
+        let error = |e: csv::Error| TrackerError::ImportError { message: e.to_string() };
+        let mut reader = csv::ReaderBuilder::new()
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(content);
+        let columns: Vec<String> = reader
+            .headers()
+            .map_err(error)?
+            .iter()
+            .map(|c| c.trim_start_matches('\u{feff}').to_lowercase())
+            .collect();
+        if let Some(c) = required.iter().find(|c| !columns.contains(&c.to_string())) {
+            return Err(TrackerError::ImportError { message: format!("column {} is missing", c) });
+        }
+
+        let mut records = Vec::new();
+        let mut skipped = Vec::new();
+        for row in reader.records() {
+            let row = match row {
+                Ok(r) => r,
+                Err(e) if !matches!(e.kind(), csv::ErrorKind::Io(_)) => {
+                    let line = e.position().map(|p| p.line()).unwrap_or_default();
+                    skipped.push(ImportSkip { line, reason: e.to_string() });
+                    continue;
+                }
+                Err(e) => {
+                    return Err(error(e));
+                }
+            };
+            let line = row.position().map(|p| p.line()).unwrap_or_default();
+            match map(&columns, &row).and_then(ImportBuilder::assert_record) {
+                Ok(r) => records.push(ImportRecord { line, ..r }),
+                Err(reason) => skipped.push(ImportSkip { line, reason }),
+            }
+        }
+        Ok((records, skipped))
+    }
+
+    fn assert_record(record: ImportRecord) -> Result<ImportRecord, String> {
+        if record.end <= record.start {
+            Err("record does not end after its start".to_owned())
+        } else {
+            Ok(record)
+        }
+    }
+
+    fn utc(&self, local: NaiveDateTime) -> Result<DateTime<Utc>, String> {
+        self.timezone
+            .utc(local)
+            .ok_or(format!("time {} does not exist in timezone {}", local, self.timezone))
+    }
+
+    fn value(columns: &[String], row: &csv::StringRecord, column: &str) -> String {
+        columns
+            .iter()
+            .position(|c| c == column)
+            .and_then(|i| row.get(i))
+            .unwrap_or_default()
+            .to_owned()
+    }
+
+    fn optional(columns: &[String], row: &csv::StringRecord, column: &str) -> Option<String> {
+        Some(ImportBuilder::value(columns, row, column)).filter(|v| !v.is_empty())
+    }
+
+    /// Tags separated by whitespace, tags with whitespace are quoted.
+    fn tags(value: &str) -> Vec<String> {
+        let mut tags = Vec::new();
+        let mut tag = String::new();
+        let mut quoted = false;
+        for c in value.chars() {
+            match c {
+                '"' => {
+                    quoted = !quoted;
+                }
+                c if c.is_whitespace() && !quoted => {
+                    if !tag.is_empty() {
+                        tags.push(std::mem::take(&mut tag));
+                    }
+                }
+                c => tag.push(c),
+            }
+        }
+        if !tag.is_empty() {
+            tags.push(tag);
+        }
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use crate::TimeDataRange;
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn import<C: AsRef<[u8]>>(folder: &Folder, format: ImportFormat, content: C) -> Result<Import, TrackerError> {
+        let file = PathBuf::from(folder.to_owned()).join("import.txt");
+        fs::write(&file, content)?;
+        Import::builder()
+            .folder(folder.to_owned())
+            .file(file)
+            .format(format)
+            .timezone(chrono_tz::Europe::Berlin.into())
+            .build()
+    }
+
+    fn statuses(t: &TimeData) -> Vec<(Status, u32)> {
+        use chrono::Timelike;
+        t.entries.data
+            .iter()
+            .map(|e| (e.status.to_owned(), e.time.hour()))
+            .collect()
+    }
+
+    #[test]
+    fn should_import_toggl() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        let content = indoc! {r#"
+            User,Email,Project,Description,Start date,Start time,End date,End time,Duration,Tags
+            Jane,jane@example.com,foo,planning,2022-02-02,09:00:00,2022-02-02,10:00:00,01:00:00,"a, b"
+            Jane,jane@example.com,bar,,2022-02-02,10:00:00,2022-02-02,13:00:00,03:00:00,
+            Jane,jane@example.com,bar,,2022-02-02,13:30:00,2022-02-02,18:00:00,04:30:00,
+            Jane,jane@example.com,bar,,2022-02-03,soon,2022-02-03,18:00:00,04:30:00,
+        "#};
+
+        let mut import = import(&folder, ImportFormat::Toggl, content)?;
+        assert_eq!(1, import.days.len());
+        assert_eq!(
+            vec![(Status::Connect, 8), (Status::Connect, 9), (Status::Break, 12), (Status::Connect, 12), (Status::End, 17)],
+            statuses(&import.days[0])
+        );
+        let first = &import.days[0].entries.data[0];
+        assert_eq!(
+            (Some("foo".to_owned()), vec!["a".to_owned(), "b".to_owned()], Some("planning".to_owned())),
+            (first.project.to_owned(), first.tags.to_owned(), first.note.to_owned())
+        );
+        assert_eq!(vec![ImportSkip { line: 5, reason: "invalid start time 'soon'".to_owned() }], import.skipped);
+        import.apply()?;
+
+        let day = NaiveDate::from_ymd_opt(2022, 2, 2).unwrap();
+        let range = TimeDataRange::builder().folder(folder.to_owned()).from(day).to(day).build()?;
+        assert_eq!(5, range.entries[0].entries.data.len());
+
+        let import = self::import(&folder, ImportFormat::Toggl, content)?;
+        assert!(import.days.is_empty());
+        assert_eq!(vec![day], import.conflicts);
+        Ok(())
+    }
+
+    #[test]
+    fn should_import_timewarrior() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        let content = indoc! {r#"
+            inc 20220202T080000Z - 20220202T110000Z # meeting "team a" # "weekly sync"
+            inc 20220202T100000Z - 20220202T120000Z
+            inc 20220202T130000Z - 20220202T160000Z
+            inc 20220203T210000Z - 20220204T030000Z # night
+            inc 20220204T080000Z
+        "#};
+
+        let import = import(&folder, ImportFormat::Timewarrior, content)?;
+        assert_eq!(2, import.days.len());
+        assert_eq!(
+            vec![(Status::Connect, 8), (Status::Break, 11), (Status::Connect, 13), (Status::End, 16)],
+            statuses(&import.days[0])
+        );
+        assert_eq!(vec![(Status::Connect, 21), (Status::End, 3)], statuses(&import.days[1]));
+        let first = &import.days[0].entries.data[0];
+        assert_eq!(vec!["meeting".to_owned(), "team a".to_owned()], first.tags);
+        assert_eq!(Some("weekly sync".to_owned()), first.note);
+        assert_eq!(
+            vec![
+                ImportSkip { line: 2, reason: "overlaps a previous record".to_owned() },
+                ImportSkip { line: 5, reason: "interval is still open".to_owned() }
+            ],
+            import.skipped
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn should_import_csv() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        let content = indoc! {"
+            start,end,project,tags
+            2022-02-02 08:00,2022-02-02 12:00,foo,a b
+            2022-02-02T13:00:00Z,2022-02-02T16:00:00Z,,
+            2022-03-27 02:30,2022-03-27 04:00,,
+            2022-02-04 08:00,2022-02-04 07:00,,
+        "};
+
+        let import = import(&folder, ImportFormat::Csv, content)?;
+        assert_eq!(1, import.days.len());
+        assert_eq!(
+            vec![(Status::Connect, 7), (Status::Break, 11), (Status::Connect, 13), (Status::End, 16)],
+            statuses(&import.days[0])
+        );
+        assert_eq!(vec!["a".to_owned(), "b".to_owned()], import.days[0].entries.data[0].tags);
+        assert_eq!(
+            vec![
+                "time 2022-03-27 02:30:00 does not exist in timezone Europe/Berlin".to_owned(),
+                "record does not end after its start".to_owned()
+            ],
+            import.skipped.iter().map(|s| s.reason.to_owned()).collect::<Vec<String>>()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn should_skip_unreadable_csv_row() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        let mut content = b"start,end,project\n2022-02-02 08:00,2022-02-02 12:00,f".to_vec();
+        content.extend_from_slice(&[0xff, 0xfe]);
+        content.extend_from_slice(b"\n2022-02-03 08:00,2022-02-03 12:00,bar\n");
+
+        let import = import(&folder, ImportFormat::Csv, content)?;
+        assert_eq!(1, import.days.len());
+        assert_eq!(Some("bar".to_owned()), import.days[0].entries.data[0].project);
+        assert_eq!(vec![2], import.skipped.iter().map(|s| s.line).collect::<Vec<u64>>());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_column() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        assert!(import(&folder, ImportFormat::Csv, "begin,end\n").is_err());
+        assert!(import(&folder, ImportFormat::Toggl, "start,end\n").is_err());
+        Ok(())
+    }
+}
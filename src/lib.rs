@@ -1,29 +1,69 @@
-#[macro_use]
-extern crate prettytable;
+mod absence;
+mod atomic_file;
+mod balance;
 mod cli;
 mod entry;
 mod errors;
+mod export;
+mod holiday;
+mod import;
+mod migration;
+mod paths;
+mod rounding;
+mod rules;
 mod settings;
+mod settings_check;
 mod status_daily;
+mod status_monthly;
+mod status_output;
+mod status_range;
 mod status_time;
 mod status_weekly;
 mod takeover;
 mod time_data;
+mod time_data_monthly;
+mod time_data_range;
 mod time_data_weekly;
+mod time_expression;
+mod timezone;
 
+pub use absence::*;
+pub use balance::*;
 pub use cli::*;
 pub use entry::*;
 pub use errors::*;
+pub use export::*;
+pub use holiday::*;
+pub use import::*;
+pub use migration::*;
+pub use paths::*;
+pub use rounding::*;
+pub use rules::*;
 pub use settings::*;
+pub use settings_check::*;
 pub use status_daily::*;
+pub use status_monthly::*;
+pub use status_output::*;
+pub use status_range::*;
 pub use status_time::*;
 pub use status_weekly::*;
 pub use takeover::*;
 pub use time_data::*;
+pub use time_data_monthly::*;
+pub use time_data_range::*;
 pub use time_data_weekly::*;
+pub use time_expression::*;
+pub use timezone::*;
 
 mod entries;
 pub(crate) use entries::*;
 
+mod file_lock;
+pub(crate) use file_lock::*;
+pub(crate) use atomic_file::*;
+
 mod models;
-pub(crate) use models::*;
\ No newline at end of file
+pub(crate) use models::*;
+
+mod status_table;
+pub(crate) use status_table::*;
\ No newline at end of file
@@ -0,0 +1,272 @@
+use std::{ collections::{ btree_map::Entry as DayEntry, BTreeMap }, fmt::Display, fs, path::PathBuf };
+
+use chrono::{ DateTime, NaiveDate, Utc };
+
+use crate::{
+    Entry,
+    Folder,
+    SESSION_GAP,
+    Status,
+    TimeData,
+    TimeDataRangeBuilder,
+    TrackerError,
+    WorkTimeZone,
+};
+
+/// Entries which are stored in the time file of another day than the local day they belong to.
+///
+/// Older versions named time files by the UTC day, so entries close to midnight may be in the wrong file.
+#[derive(Default, Debug)]
+pub struct Migration {
+    pub moves: Vec<MigrationMove>,
+    days: BTreeMap<NaiveDate, TimeData>,
+    timezone: WorkTimeZone,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationMove {
+    pub status: Status,
+    pub time: DateTime<Utc>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl Migration {
+    pub fn builder() -> MigrationBuilder {
+        MigrationBuilder::default()
+    }
+
+    /// Write the moved entries into their time files, files without any data left are removed.
+    pub fn apply(&mut self) -> Result<&mut Self, TrackerError> {
+        let changed: Vec<NaiveDate> = self.moves
+            .iter()
+            .flat_map(|m| [m.from, m.to])
+            .collect();
+        for (day, time_data) in self.days.iter_mut() {
+            if !changed.contains(day) {
+                time_data.unlock();
+                continue;
+            }
+            if time_data.entries.data.is_empty() && time_data.entries.note.is_none() {
+                log::info!("remove empty time file of {}", day);
+                if time_data.file.exists() {
+                    fs::remove_file(&time_data.file)?;
+                }
+                time_data.unlock();
+            } else {
+                log::info!("write migrated time file of {}", day);
+                time_data.write_to_file()?;
+            }
+        }
+        Ok(self)
+    }
+}
+
+impl Display for Migration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.moves.is_empty() {
+            return write!(f, "All entries are in the time file of their day.");
+        }
+        let lines = self.moves
+            .iter()
+            .map(|m| {
+                format!(
+                    "{} {:10} {} -> {}",
+                    self.timezone.local(m.time).format("%Y-%m-%d %H:%M"),
+                    format!("{:?}", m.status),
+                    m.from.format("%Y%m%d.json"),
+                    m.to.format("%Y%m%d.json")
+                )
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[derive(Default)]
+pub struct MigrationBuilder {
+    folder: Option<Folder>,
+    timezone: WorkTimeZone,
+}
+
+impl MigrationBuilder {
+    pub fn folder(&mut self, folder: Folder) -> &mut Self {
+        self.folder = Some(folder);
+        self
+    }
+
+    /// Timezone the days are based on, the system timezone by default.
+    pub fn timezone(&mut self, timezone: WorkTimeZone) -> &mut Self {
+        self.timezone = timezone;
+        self
+    }
+
+    /// Read and lock all time files and regroup their entries by local day.
+    ///
+    /// A session continues past midnight while it is not ended and its entries are less than 12 hours apart.
+    pub fn build(&self) -> Result<Migration, TrackerError> {
+        let folder = match self.folder.to_owned() {
+            Some(f) => f,
+            None => {
+                return Err(TrackerError::MigrationError {
+                    message: "folder is not defined".to_owned(),
+                });
+            }
+        };
+
+        let mut days: BTreeMap<NaiveDate, TimeData> = BTreeMap::new();
+        for day in TimeDataRangeBuilder::files(&PathBuf::from(folder.to_owned()))?.into_keys() {
+            let mut t = TimeData::builder().folder(folder.to_owned()).day(day).build()?;
+            t.read_from_file()?;
+            days.insert(day, t);
+        }
+
+        let mut entries: Vec<(NaiveDate, Entry)> = days
+            .iter_mut()
+            .flat_map(|(day, t)| t.entries.data.drain(..).map(|e| (*day, e)))
+            .collect();
+        entries.sort_by_key(|(_, e)| e.time);
+
+        // entries of a session which continues past midnight stay with its start day
+        let mut moves: Vec<MigrationMove> = Vec::new();
+        let mut session: Option<(NaiveDate, Entry)> = None;
+        for (from, e) in entries {
+            let to = match session.as_ref() {
+                Some((d, last)) if last.status != Status::End && e.time - last.time < SESSION_GAP => *d,
+                _ => self.timezone.date(e.time),
+            };
+            if to != from {
+                log::debug!("move {:?} at {} from {} to {}", e.status, e.time, from, to);
+                moves.push(MigrationMove { status: e.status.to_owned(), time: e.time, from, to });
+            }
+            session = Some((to, e.to_owned()));
+
+            let time_data = match days.entry(to) {
+                DayEntry::Occupied(o) => o.into_mut(),
+                DayEntry::Vacant(v) => v.insert(TimeData::builder().folder(folder.to_owned()).day(to).build()?),
+            };
+            time_data.entries.data.push(e);
+        }
+
+        for time_data in days.values_mut() {
+            time_data.renumber();
+        }
+        Ok(Migration { moves, days, timezone: self.timezone })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{ Duration, Local, TimeZone };
+
+    use crate::{ Entries, FileLock, TimeDataRange };
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn entry(id: u8, status: Status, time: DateTime<Local>) -> Entry {
+        Entry { id, status, time: time.to_utc(), ..Default::default() }
+    }
+
+    /// Write entries into the file of `day` like older versions did.
+    fn write(folder: &Folder, day: NaiveDate, data: Vec<Entry>) -> Result<(), TrackerError> {
+        let mut t = TimeData::builder().folder(folder.to_owned()).day(day).build()?;
+        t.entries = Entries { data, ..Default::default() };
+        t.write_to_file()
+    }
+
+    #[test]
+    fn should_move_entries_to_local_day() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        let start = Local.with_ymd_and_hms(2022, 3, 1, 8, 0, 0).unwrap();
+        let day = start.date_naive();
+        let previous = day.pred_opt().unwrap();
+        let next = day.succ_opt().unwrap();
+
+        // start filed a day early, end filed a day late and a day which only has misplaced entries
+        write(&folder, previous, vec![entry(1, Status::Connect, start)])?;
+        write(&folder, day, vec![entry(1, Status::Break, start + Duration::hours(4))])?;
+        write(&folder, next, vec![entry(1, Status::End, start + Duration::hours(9))])?;
+
+        let mut migration = Migration::builder().folder(folder.to_owned()).build()?;
+        assert_eq!(2, migration.moves.len());
+        assert_eq!(
+            MigrationMove { status: Status::Connect, time: start.to_utc(), from: previous, to: day },
+            migration.moves[0]
+        );
+        migration.apply()?;
+
+        let range = TimeDataRange::builder().folder(folder.to_owned()).from(previous).to(next).build()?;
+        let data: Vec<Vec<(u8, Status)>> = range.entries
+            .iter()
+            .map(|t| t.entries.data.iter().map(|e| (e.id, e.status.to_owned())).collect())
+            .collect();
+        assert_eq!(
+            vec![
+                vec![],
+                vec![(1, Status::Connect), (2, Status::Break), (3, Status::End)],
+                vec![]
+            ],
+            data
+        );
+        assert!(!temp_dir.path().join(previous.format("%Y%m%d.json").to_string()).exists());
+        assert!(!temp_dir.path().join(next.format("%Y%m%d.json").to_string()).exists());
+        let file = temp_dir.path().join(day.format("%Y%m%d.json").to_string());
+        assert!(FileLock::acquire(&file, std::time::Duration::from_millis(100)).is_ok());
+
+        let migration = Migration::builder().folder(folder).build()?;
+        assert!(migration.moves.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_night_shift_in_start_day() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        let start = Local.with_ymd_and_hms(2022, 3, 1, 22, 0, 0).unwrap();
+        let day = start.date_naive();
+        let next = day.succ_opt().unwrap();
+
+        write(&folder, day, vec![entry(1, Status::Connect, start)])?;
+        write(&folder, next, vec![entry(1, Status::End, start + Duration::hours(8))])?;
+
+        let mut migration = Migration::builder().folder(folder).build()?;
+        assert_eq!(1, migration.moves.len());
+        assert_eq!((next, day), (migration.moves[0].from, migration.moves[0].to));
+        migration.apply()?;
+        assert!(!temp_dir.path().join(next.format("%Y%m%d.json").to_string()).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn should_keep_day_note() -> Result<(), TrackerError> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let folder: Folder = temp_dir.path().to_path_buf().into();
+        let start = Local.with_ymd_and_hms(2022, 3, 1, 8, 0, 0).unwrap();
+        let previous = start.date_naive().pred_opt().unwrap();
+
+        let mut t = TimeData::builder().folder(folder.to_owned()).day(previous).build()?;
+        t.entries = Entries {
+            data: vec![entry(1, Status::Connect, start)],
+            note: Some("misfiled".to_owned()),
+            ..Default::default()
+        };
+        t.write_to_file()?;
+
+        Migration::builder().folder(folder.to_owned()).build()?.apply()?;
+
+        let mut t = TimeData::builder().folder(folder).day(previous).build()?;
+        t.read_only()?;
+        assert!(t.entries.data.is_empty());
+        assert_eq!(Some("misfiled".to_owned()), t.entries.note);
+        Ok(())
+    }
+}
@@ -1,6 +1,6 @@
 use std::io;
 
-use crate::{ Entries, Entry };
+use crate::{ Entries, Entry, CURRENT_VERSION };
 
 use super::UpgradeError;
 
@@ -9,17 +9,40 @@ pub struct Upgrade {}
 impl Upgrade {
     pub fn to_v1<R: io::Read>(reader: R) -> Result<Option<Entries>, UpgradeError> {
         let vec_entries: Result<Vec<Entry>, serde_json::Error> = serde_json::from_reader(reader);
-        if vec_entries.is_err() {
-            let err = vec_entries.unwrap_err();
-            if err.is_data() || err.is_eof() {
-                return Ok(None);
+        match vec_entries {
+            Err(err) if err.is_data() || err.is_eof() => Ok(None),
+            Err(err) => Err(UpgradeError::UpgradeV1Error(err)),
+            Ok(mut vec_entries) => {
+                let mut entries = Entries::new();
+                entries.version = 1;
+                entries.append(&mut vec_entries);
+                Ok(Some(entries))
             }
-            return Err(UpgradeError::UpgradeV1Error(err));
         }
+    }
 
-        let mut entries = Entries::new();
-        entries.append(&mut vec_entries.unwrap());
-        Ok(Some(entries))
+    /// Version 2 adds optional project and tags to entries, which default to none.
+    pub fn to_v2(mut entries: Entries) -> Result<Entries, UpgradeError> {
+        match entries.version {
+            1 => {
+                entries.version = 2;
+                Ok(entries)
+            }
+            v if v <= CURRENT_VERSION => Ok(entries),
+            v => Err(UpgradeError::UnsupportedVersion(v)),
+        }
+    }
+
+    /// Version 3 adds optional notes to entries and the day, which default to none.
+    pub fn to_v3(mut entries: Entries) -> Result<Entries, UpgradeError> {
+        match entries.version {
+            2 => {
+                entries.version = 3;
+                Ok(entries)
+            }
+            v if v <= CURRENT_VERSION => Ok(entries),
+            v => Err(UpgradeError::UnsupportedVersion(v)),
+        }
     }
 }
 
@@ -49,6 +72,36 @@ mod tests {
         assert!(res.unwrap().is_none())
     }
 
+    #[test]
+    fn should_upgrade_to_v2() {
+        let content =
+            "{\"data\":[{\"id\":1,\"status\":\"Connect\",\"time\":\"2022-02-02T00:00:53.523319900Z\"}],\"version\":1}";
+        let entries = serde_json::from_str(content).unwrap();
+        let res = Upgrade::to_v2(entries).unwrap();
+        assert_eq!(res.version, 2);
+        assert!(res.data[0].project.is_none());
+        assert!(res.data[0].tags.is_empty());
+    }
+
+    #[test]
+    fn should_upgrade_v1_to_v3() {
+        let content =
+            "[{\"id\":1,\"status\":\"Connect\",\"time\":\"2022-02-02T00:00:53.523319900Z\"}]";
+        let entries = Upgrade::to_v1(content.as_bytes()).unwrap().unwrap();
+        let res = Upgrade::to_v3(Upgrade::to_v2(entries).unwrap()).unwrap();
+        assert_eq!(res.version, 3);
+        assert!(res.note.is_none());
+        assert!(res.data[0].note.is_none());
+    }
+
+    #[test]
+    fn should_not_upgrade_newer_version() {
+        let content = "{\"data\":[],\"version\":99}";
+        let entries = serde_json::from_str(content).unwrap();
+        let res = Upgrade::to_v2(entries);
+        assert_eq!("unsupported time file version 99", res.err().unwrap().to_string());
+    }
+
     #[test]
     fn should_panic_on_malformed() {
         let content =
@@ -4,4 +4,6 @@ use thiserror::Error;
 pub enum UpgradeError {
     #[error("error upgrading to v1")]
     UpgradeV1Error(#[from] serde_json::Error),
+    #[error("unsupported time file version {0}")]
+    UnsupportedVersion(u8),
 }
\ No newline at end of file
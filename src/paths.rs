@@ -0,0 +1,197 @@
+use std::{ env, fs, path::{ Path, PathBuf } };
+
+use config::ConfigError;
+
+use crate::{ write_json, TrackerError };
+
+/// Environment variable with the configuration file.
+pub const CONFIG_ENV: &str = "TRACKRS_CONFIG";
+
+/// Legacy configuration file in the home folder.
+const LEGACY_CONFIG: &str = ".trackrs";
+/// Legacy takeover file in the home folder, followed by `-<profile>` for profiles.
+const LEGACY_TAKEOVER: &str = ".trackrs-takeover";
+
+/// Location of the configuration file and of data kept outside the tracking folder, eg. takeover minutes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SettingsPaths {
+    pub config: PathBuf,
+    pub data: PathBuf,
+}
+
+impl SettingsPaths {
+    /// Given configuration file, its data is kept in the same folder.
+    pub fn file(config: PathBuf) -> Self {
+        let data = config.parent().map(Path::to_path_buf).unwrap_or_default();
+        SettingsPaths { config, data }
+    }
+
+    /// The `config` file, otherwise `TRACKRS_CONFIG`, otherwise the XDG paths.
+    pub fn resolve(config: Option<PathBuf>) -> Result<Self, TrackerError> {
+        match config.or_else(|| env::var_os(CONFIG_ENV).map(PathBuf::from)) {
+            Some(c) => Ok(SettingsPaths::file(c)),
+            None => {
+                let home = dirs::home_dir().ok_or_else(|| {
+                    TrackerError::TrackerConfigError(ConfigError::Message("home folder not found".to_owned()))
+                })?;
+                SettingsPaths::xdg(&home, env::var_os("XDG_CONFIG_HOME"), env::var_os("XDG_DATA_HOME"))
+            }
+        }
+    }
+
+    /// `$XDG_CONFIG_HOME/trackrs/config.json` and `$XDG_DATA_HOME/trackrs`, the legacy files of the home folder are
+    /// moved there.
+    fn xdg<P: Into<PathBuf>>(home: &Path, config_home: Option<P>, data_home: Option<P>) -> Result<Self, TrackerError> {
+        // relative paths are invalid and ignored by the specification
+        let base = |path: Option<P>, default: &str| match path.map(Into::into) {
+            Some(p) if p.is_absolute() => p,
+            _ => home.join(default),
+        };
+        let paths = SettingsPaths {
+            config: base(config_home, ".config").join("trackrs").join("config.json"),
+            data: base(data_home, ".local/share").join("trackrs"),
+        };
+        paths.migrate(home)?;
+        Ok(paths)
+    }
+
+    /// Takeover file of the profile.
+    pub fn takeover(&self, profile: Option<&str>) -> PathBuf {
+        match profile {
+            Some(p) => self.data.join(format!("takeover-{}.json", p)),
+            None => self.data.join("takeover.json"),
+        }
+    }
+
+    /// Move the legacy configuration and takeover files, unless the configuration file exists.
+    fn migrate(&self, home: &Path) -> Result<(), TrackerError> {
+        let legacy = home.join(LEGACY_CONFIG);
+        if self.config.exists() || !legacy.is_file() {
+            return Ok(());
+        }
+        log::info!("move configuration {:?} to {:?}", legacy, self.config);
+        SettingsPaths::move_config(&legacy, &self.config, home)?;
+
+        for entry in fs::read_dir(home)? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            let file = match name.strip_prefix(LEGACY_TAKEOVER) {
+                Some("") => self.takeover(None),
+                Some(p) if p.starts_with('-') => self.takeover(Some(&p[1..])),
+                _ => continue,
+            };
+            log::info!("move takeover {} to {:?}", name, file);
+            SettingsPaths::move_file(&home.join(name), &file)?;
+        }
+        Ok(())
+    }
+
+    /// Move the configuration, holiday calendars relative to the home folder are made absolute.
+    fn move_config(from: &Path, to: &Path, home: &Path) -> Result<(), TrackerError> {
+        let mut config = match serde_json::from_str::<serde_json::Value>(&fs::read_to_string(from)?) {
+            Ok(c) => c,
+            Err(_) => {
+                return SettingsPaths::move_file(from, to);
+            }
+        };
+        let mut changed = false;
+        if let Some(holidays) = config.get_mut("holidays").and_then(|h| h.as_array_mut()) {
+            for h in holidays.iter_mut() {
+                if let Some(p) = h.as_str().map(PathBuf::from).filter(|p| p.is_relative()) {
+                    log::info!("holiday calendar {:?} is now read from the home folder", p);
+                    *h = home.join(p).to_string_lossy().into();
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return SettingsPaths::move_file(from, to);
+        }
+        if let Some(p) = to.parent() {
+            fs::create_dir_all(p)?;
+        }
+        write_json(to, &config, true)?;
+        fs::remove_file(from)?;
+        Ok(())
+    }
+
+    /// Rename, or copy and remove across file systems.
+    fn move_file(from: &Path, to: &Path) -> Result<(), TrackerError> {
+        if let Some(p) = to.parent() {
+            fs::create_dir_all(p)?;
+        }
+        if fs::rename(from, to).is_err() {
+            fs::copy(from, to)?;
+            fs::remove_file(from)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    #[test]
+    fn should_use_file() {
+        let paths = SettingsPaths::file("/temp/dir/trackrs.json".into());
+        assert_eq!(PathBuf::from("/temp/dir/trackrs.json"), paths.config);
+        assert_eq!(PathBuf::from("/temp/dir"), paths.data);
+        assert_eq!(PathBuf::from("/temp/dir/takeover.json"), paths.takeover(None));
+        assert_eq!(PathBuf::from("/temp/dir/takeover-client.json"), paths.takeover(Some("client")));
+    }
+
+    #[test]
+    fn should_use_xdg() -> Result<(), TrackerError> {
+        logger();
+        let home = tempfile::tempdir()?;
+        let paths = SettingsPaths::xdg(home.path(), None::<PathBuf>, None)?;
+        assert_eq!(home.path().join(".config/trackrs/config.json"), paths.config);
+        assert_eq!(home.path().join(".local/share/trackrs"), paths.data);
+
+        let paths = SettingsPaths::xdg(home.path(), Some("/xdg/config"), Some("relative"))?;
+        assert_eq!(PathBuf::from("/xdg/config/trackrs/config.json"), paths.config);
+        assert_eq!(home.path().join(".local/share/trackrs"), paths.data);
+        Ok(())
+    }
+
+    #[test]
+    fn should_migrate_legacy_files() -> Result<(), TrackerError> {
+        logger();
+        let home = tempfile::tempdir()?;
+        fs::write(home.path().join(".trackrs"), r#"{"folder": "/temp/dir"}"#)?;
+        fs::write(home.path().join(".trackrs-takeover"), r#"{"minutes":15}"#)?;
+        fs::write(home.path().join(".trackrs-takeover-client"), r#"{"minutes":30}"#)?;
+        fs::write(home.path().join(".trackrs-takeoverx"), "")?;
+
+        let paths = SettingsPaths::xdg(home.path(), None::<PathBuf>, None)?;
+
+        assert_eq!(r#"{"folder": "/temp/dir"}"#, fs::read_to_string(&paths.config)?);
+        assert_eq!(r#"{"minutes":15}"#, fs::read_to_string(paths.takeover(None))?);
+        assert_eq!(r#"{"minutes":30}"#, fs::read_to_string(paths.takeover(Some("client")))?);
+        assert!(!home.path().join(".trackrs").exists());
+        assert!(!home.path().join(".trackrs-takeover-client").exists());
+        assert!(home.path().join(".trackrs-takeoverx").exists());
+
+        // holiday calendars stay in the home folder
+        fs::remove_file(&paths.config)?;
+        fs::write(home.path().join(".trackrs"), r#"{"folder": "/temp/dir", "holidays": ["holidays.json", "/x.ics"]}"#)?;
+        SettingsPaths::xdg(home.path(), None::<PathBuf>, None)?;
+        let config: serde_json::Value = serde_json::from_str(&fs::read_to_string(&paths.config)?)?;
+        assert_eq!(home.path().join("holidays.json").to_str().unwrap(), config["holidays"][0]);
+        assert_eq!("/x.ics", config["holidays"][1]);
+        assert_eq!("/temp/dir", config["folder"]);
+        assert!(!home.path().join(".trackrs").exists());
+
+        // an existing configuration is kept
+        fs::write(home.path().join(".trackrs"), r#"{"folder": "/legacy"}"#)?;
+        SettingsPaths::xdg(home.path(), None::<PathBuf>, None)?;
+        assert!(fs::read_to_string(&paths.config)?.contains("holidays.json"));
+        assert!(home.path().join(".trackrs").exists());
+        Ok(())
+    }
+}
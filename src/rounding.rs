@@ -0,0 +1,84 @@
+use chrono::Duration;
+use serde::{ Deserialize, Serialize };
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RoundingMode {
+    Up,
+    Down,
+    #[default]
+    Nearest,
+}
+
+/// Rounding of a time of day or a duration to a step of minutes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rounding {
+    /// Step in minutes, eg. 5, 15 or 30, 0 disables the rounding.
+    #[serde(default)]
+    pub minutes: u16,
+    #[serde(default)]
+    pub mode: RoundingMode,
+}
+
+/// Rounding of reported times for payroll, tracked entries are not changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoundingSettings {
+    #[serde(default)]
+    pub start: Rounding,
+    #[serde(default)]
+    pub end: Rounding,
+    /// Applied to the work time after it is calculated from the rounded start and end.
+    #[serde(default)]
+    pub worktime: Rounding,
+}
+
+impl Rounding {
+    pub fn apply(&self, duration: Duration) -> Duration {
+        if self.minutes == 0 {
+            return duration;
+        }
+        let step = i64::from(self.minutes) * 60;
+        let seconds = duration.num_seconds();
+        let steps = match self.mode {
+            RoundingMode::Up => seconds.div_euclid(step) + i64::from(seconds.rem_euclid(step) > 0),
+            RoundingMode::Down => seconds.div_euclid(step),
+            RoundingMode::Nearest => (seconds + step / 2).div_euclid(step),
+        };
+        Duration::seconds(steps * step)
+    }
+}
+
+impl RoundingSettings {
+    pub fn enabled(&self) -> bool {
+        [self.start, self.end, self.worktime].iter().any(|r| r.minutes > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_round() {
+        let rounding = |minutes: u16, mode: RoundingMode| Rounding { minutes, mode };
+        let time = Duration::hours(8) + Duration::minutes(7);
+
+        assert_eq!(Duration::hours(8) + Duration::minutes(15), rounding(15, RoundingMode::Up).apply(time));
+        assert_eq!(Duration::hours(8), rounding(15, RoundingMode::Down).apply(time));
+        assert_eq!(Duration::hours(8) + Duration::minutes(5), rounding(5, RoundingMode::Nearest).apply(time));
+        assert_eq!(Duration::hours(8), rounding(30, RoundingMode::Nearest).apply(time));
+        assert_eq!(Duration::hours(8), rounding(15, RoundingMode::Up).apply(Duration::hours(8)));
+        assert_eq!(time, Rounding::default().apply(time));
+    }
+
+    #[test]
+    fn should_deserialize() {
+        let settings: RoundingSettings = serde_json::from_str(
+            r#"{"start": {"minutes": 15, "mode": "up"}, "end": {"minutes": 15, "mode": "down"}}"#
+        ).unwrap();
+        assert_eq!(Rounding { minutes: 15, mode: RoundingMode::Up }, settings.start);
+        assert_eq!(Rounding::default(), settings.worktime);
+        assert!(settings.enabled());
+        assert!(!RoundingSettings::default().enabled());
+    }
+}
@@ -0,0 +1,339 @@
+use std::fmt::Display;
+
+use chrono::{ DateTime, Duration, Utc };
+use colored::Colorize;
+
+use crate::{ Settings, Status, StatusTime, TimeData, TrackerError };
+
+/// Running days are warned this long before a limit is reached.
+const RULE_NOTICE: Duration = Duration::minutes(30);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RuleSeverity {
+    /// A limit is about to be reached or a break does not count.
+    Warning,
+    /// A limit is exceeded.
+    Violation,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RuleFinding {
+    pub severity: RuleSeverity,
+    pub message: String,
+}
+
+/// Findings of the labor rules of [`crate::RuleSettings`] and the break `limits` for a day.
+#[derive(Clone, Debug, Default)]
+pub struct RuleCheck {
+    pub findings: Vec<RuleFinding>,
+}
+
+impl RuleCheck {
+    pub fn builder() -> RuleCheckBuilder {
+        RuleCheckBuilder::default()
+    }
+}
+
+impl Display for RuleCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.findings
+            .iter()
+            .map(|r| match r.severity {
+                RuleSeverity::Warning => format!("{:width$}{}", "Warning:", r.message, width = 13).bright_yellow(),
+                RuleSeverity::Violation => format!("{:width$}{}", "Violation:", r.message, width = 13).bright_red(),
+            })
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[derive(Default)]
+pub struct RuleCheckBuilder {
+    data: Option<TimeData>,
+    previous: Option<TimeData>,
+    settings: Option<Settings>,
+    now: Option<DateTime<Utc>>,
+}
+
+impl RuleCheckBuilder {
+    pub fn data(&mut self, data: TimeData) -> &mut Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Day before `data`, for the rest between both days.
+    pub fn previous(&mut self, previous: TimeData) -> &mut Self {
+        self.previous = Some(previous);
+        self
+    }
+
+    pub fn settings(&mut self, settings: Settings) -> &mut Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    /// End of a running day, now by default.
+    pub fn now(&mut self, now: DateTime<Utc>) -> &mut Self {
+        self.now = Some(now);
+        self
+    }
+
+    pub fn build(&self) -> Result<RuleCheck, TrackerError> {
+        let (data, settings) = match (self.data.as_ref(), self.settings.as_ref()) {
+            (Some(d), Some(s)) => (d, s),
+            _ => {
+                return Err(TrackerError::StatusError {
+                    message: "data and settings are required for the rule check".to_owned(),
+                });
+            }
+        };
+        let now = self.now.unwrap_or_else(Utc::now);
+        let running = data.continues_at(now);
+        let segments = RuleCheckBuilder::segments(data, now, running);
+        let rules = &settings.rules;
+        let minutes = |m: u16| Duration::minutes(m.into());
+        let clock = |t: DateTime<Utc>| StatusTime::at(t, &settings.timezone).to_string();
+        let mut findings = Vec::new();
+        let mut finding = |severity: RuleSeverity, message: String| {
+            log::debug!("{:?}: {}", severity, message);
+            findings.push(RuleFinding { severity, message });
+        };
+
+        // breaks shorter than the minimum do not interrupt a work block
+        let mut blocks: Vec<(DateTime<Utc>, DateTime<Utc>)> = Vec::new();
+        let mut r#break = Duration::zero();
+        for (start, end) in segments.iter() {
+            match blocks.last_mut() {
+                Some((_, e)) if *start - *e < minutes(rules.min_break) => {
+                    finding(
+                        RuleSeverity::Warning,
+                        format!(
+                            "break at {} of {} is shorter than {} and does not count",
+                            clock(*e),
+                            StatusTime::from(*start - *e),
+                            StatusTime::from(minutes(rules.min_break))
+                        )
+                    );
+                    *e = *end;
+                }
+                Some((_, e)) => {
+                    r#break += *start - *e;
+                    blocks.push((*start, *end));
+                }
+                None => blocks.push((*start, *end)),
+            }
+        }
+        let worktime = segments.iter().fold(Duration::zero(), |w, (s, e)| w + (*e - *s));
+
+        if rules.max_block > 0 {
+            for (start, end) in blocks.iter() {
+                let left = minutes(rules.max_block) - (*end - *start);
+                if left < Duration::zero() {
+                    finding(
+                        RuleSeverity::Violation,
+                        format!("worked {} without a break since {}", StatusTime::from(*end - *start), clock(*start))
+                    );
+                } else if running && *end == now && left <= RULE_NOTICE {
+                    finding(RuleSeverity::Warning, format!("take a break before {}", clock(now + left)));
+                }
+            }
+        }
+
+        if rules.max_worktime > 0 {
+            let left = minutes(rules.max_worktime) - worktime;
+            if left < Duration::zero() {
+                finding(
+                    RuleSeverity::Violation,
+                    format!(
+                        "work time of {} exceeds {}",
+                        StatusTime::from(worktime),
+                        StatusTime::from(minutes(rules.max_worktime))
+                    )
+                );
+            } else if running && left <= RULE_NOTICE {
+                finding(RuleSeverity::Warning, format!("maximum work time is reached at {}", clock(now + left)));
+            }
+        }
+
+        let required = settings.limits
+            .iter()
+            .filter(|l| worktime > minutes(l.start))
+            .map(|l| Duration::minutes(l.minutes.into()))
+            .max()
+            .unwrap_or_default();
+        if r#break < required {
+            let severity = if running { RuleSeverity::Warning } else { RuleSeverity::Violation };
+            finding(
+                severity,
+                format!("break of {} is less than the required {}", StatusTime::from(r#break), StatusTime::from(required))
+            );
+        }
+
+        let last_end = self.previous.as_ref().and_then(|p| p.entries.data.last()).map(|e| e.time);
+        if let (true, Some(end), Some((start, _))) = (rules.min_rest > 0, last_end, segments.first()) {
+            if *start - end < minutes(rules.min_rest) {
+                finding(
+                    RuleSeverity::Violation,
+                    format!(
+                        "rest of {} since the previous day is less than {}",
+                        StatusTime::from(*start - end),
+                        StatusTime::from(minutes(rules.min_rest))
+                    )
+                );
+            }
+        }
+
+        Ok(RuleCheck { findings })
+    }
+
+    /// Work segments from a connect until the next break, disconnect or end.
+    ///
+    /// The last segment of a running day ends now, of another day without end at its last entry.
+    fn segments(data: &TimeData, now: DateTime<Utc>, running: bool) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+        let mut segments = Vec::new();
+        let mut start: Option<DateTime<Utc>> = None;
+        for e in data.entries.data.iter() {
+            match (&e.status, start) {
+                (Status::Connect, None) => {
+                    start = Some(e.time);
+                }
+                (Status::Break | Status::Disconnect | Status::End, Some(s)) => {
+                    segments.push((s, e.time));
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let (Some(s), Some(last)) = (start, data.entries.data.last()) {
+            segments.push((s, if running { now } else { last.time }));
+        }
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use crate::{ BreakLimit, Entries, Entry, RuleSettings };
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn time(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 2, day, hour, minute, 0).unwrap()
+    }
+
+    fn data(entries: &[(Status, DateTime<Utc>)]) -> TimeData {
+        TimeData {
+            entries: Entries {
+                data: entries
+                    .iter()
+                    .enumerate()
+                    .map(|(n, (status, time))| Entry {
+                        id: (n + 1) as u8,
+                        status: status.to_owned(),
+                        time: time.to_owned(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    fn settings() -> Settings {
+        Settings {
+            timezone: chrono_tz::UTC.into(),
+            limits: vec![BreakLimit { start: 6 * 60, minutes: 30 }, BreakLimit { start: 9 * 60, minutes: 45 }],
+            rules: RuleSettings { min_break: 15, max_block: 6 * 60, max_worktime: 10 * 60, min_rest: 11 * 60 },
+            ..Default::default()
+        }
+    }
+
+    fn messages(check: &RuleCheck) -> Vec<(RuleSeverity, &str)> {
+        check.findings
+            .iter()
+            .map(|f| (f.severity, f.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn should_comply() -> Result<(), TrackerError> {
+        logger();
+        let data = data(
+            &[
+                (Status::Connect, time(2, 8, 0)),
+                (Status::Break, time(2, 12, 0)),
+                (Status::Connect, time(2, 12, 30)),
+                (Status::End, time(2, 16, 30)),
+            ]
+        );
+        let check = RuleCheck::builder().data(data).settings(settings()).build()?;
+        assert!(check.findings.is_empty(), "{}", check);
+        Ok(())
+    }
+
+    #[test]
+    fn should_not_count_short_breaks() -> Result<(), TrackerError> {
+        logger();
+        let data = data(
+            &[
+                (Status::Connect, time(2, 6, 30)),
+                (Status::Break, time(2, 9, 0)),
+                (Status::Connect, time(2, 9, 10)),
+                (Status::Break, time(2, 13, 0)),
+                (Status::Connect, time(2, 13, 15)),
+                (Status::End, time(2, 16, 45)),
+            ]
+        );
+        let check = RuleCheck::builder().data(data).settings(settings()).build()?;
+        assert_eq!(
+            vec![
+                (RuleSeverity::Warning, "break at 09:00 of 00:10 is shorter than 00:15 and does not count"),
+                (RuleSeverity::Violation, "worked 06:30 without a break since 06:30"),
+                (RuleSeverity::Violation, "break of 00:15 is less than the required 00:45")
+            ],
+            messages(&check)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn should_exceed_worktime_and_rest() -> Result<(), TrackerError> {
+        logger();
+        let previous = data(&[(Status::Connect, time(1, 14, 0)), (Status::End, time(1, 22, 0))]);
+        let data = data(
+            &[
+                (Status::Connect, time(2, 6, 0)),
+                (Status::Break, time(2, 11, 0)),
+                (Status::Connect, time(2, 11, 45)),
+                (Status::End, time(2, 17, 15)),
+            ]
+        );
+        let check = RuleCheck::builder().data(data).previous(previous).settings(settings()).build()?;
+        assert_eq!(
+            vec![
+                (RuleSeverity::Violation, "work time of 10:30 exceeds 10:00"),
+                (RuleSeverity::Violation, "rest of 08:00 since the previous day is less than 11:00")
+            ],
+            messages(&check)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn should_warn_running_day() -> Result<(), TrackerError> {
+        logger();
+        let data = data(&[(Status::Connect, time(2, 8, 0))]);
+        let check = RuleCheck::builder().data(data).settings(settings()).now(time(2, 13, 45)).build()?;
+        assert_eq!(vec![(RuleSeverity::Warning, "take a break before 14:00")], messages(&check));
+        Ok(())
+    }
+}
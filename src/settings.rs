@@ -1,11 +1,22 @@
-use std::{ env, fs::OpenOptions, path::Path };
+use std::{ collections::BTreeMap, env, fs, path::{ Path, PathBuf } };
 
-use chrono::{ DateTime, Datelike, Utc };
+use chrono::{ DateTime, Datelike, NaiveDate, Utc, Weekday };
 use config::{ Config, ConfigError, File, FileFormat, Map, Value, ValueKind };
 use serde::Serialize;
 use serde_derive::Deserialize;
 
-use crate::TrackerError;
+use crate::{
+    write_json,
+    HolidayCalendar,
+    RoundingSettings,
+    SettingsCheck,
+    SettingsPaths,
+    TrackerError,
+    WorkTimeZone,
+};
+
+/// Environment variable selecting the active profile.
+pub const PROFILE_ENV: &str = "TRACKRS_PROFILE";
 
 #[derive(Serialize)]
 #[allow(unused)]
@@ -21,6 +32,78 @@ pub struct Settings {
     pub threshold_limits: u8,
     pub limits: Vec<BreakLimit>,
     pub workperday: WorkPerDayInMinutes,
+    #[serde(default)]
+    pub balance: BalanceSettings,
+    #[serde(default)]
+    pub absence: AbsenceSettings,
+    /// Holiday calendar files, either `.ics` or a JSON list of dates.
+    #[serde(default)]
+    pub holidays: Vec<String>,
+    /// Timezone of the work location, eg. `Europe/Berlin`.
+    #[serde(default)]
+    pub timezone: WorkTimeZone,
+    #[serde(default)]
+    pub rules: RuleSettings,
+    #[serde(default)]
+    pub rounding: RoundingSettings,
+    /// Named profiles, eg. per employer, replacing the settings above when active.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileSettings>,
+    /// Name of the active profile.
+    #[serde(skip)]
+    pub profile: Option<String>,
+    #[serde(skip)]
+    pub paths: SettingsPaths,
+}
+
+/// Settings of a profile, unset values are taken from the shared settings.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+#[allow(unused)]
+pub struct ProfileSettings {
+    pub folder: String,
+    #[serde(default)]
+    pub limits: Option<Vec<BreakLimit>>,
+    #[serde(default)]
+    pub workperday: Option<WorkPerDayInMinutes>,
+}
+
+/// Starting point of the overtime balance.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[allow(unused)]
+pub struct BalanceSettings {
+    /// Overtime in minutes at the start of the balance, negative for missing time.
+    #[serde(default)]
+    pub start: i64,
+    /// Day the balance starts, earlier time files are ignored.
+    #[serde(default)]
+    pub reset: Option<NaiveDate>,
+}
+
+/// Allowances for absences.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[allow(unused)]
+pub struct AbsenceSettings {
+    /// Vacation days per year, 0 disables the quota.
+    #[serde(default)]
+    pub vacation: u16,
+}
+
+/// Labor rules checked by `status` and `end`, all in minutes and disabled with 0.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[allow(unused)]
+pub struct RuleSettings {
+    /// Shorter breaks do not count as break.
+    #[serde(default)]
+    pub min_break: u16,
+    /// Longest work time without a break.
+    #[serde(default)]
+    pub max_block: u16,
+    /// Longest work time of a day.
+    #[serde(default)]
+    pub max_worktime: u16,
+    /// Shortest rest between the end of a day and the start of the next one.
+    #[serde(default)]
+    pub min_rest: u16,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
@@ -45,7 +128,7 @@ impl From<BreakLimit> for config::Value {
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 #[allow(unused)]
 pub struct WorkPerDayInMinutes {
     pub monday: u16,
@@ -55,6 +138,38 @@ pub struct WorkPerDayInMinutes {
     pub friday: u16,
     pub saturday: u16,
     pub sunday: u16,
+    /// Work time on holidays, read from the holiday calendars.
+    #[serde(skip)]
+    pub holidays: BTreeMap<NaiveDate, u16>,
+    /// Schedules replacing the weekday values above for their days, the first matching one applies.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub schedules: Vec<WorkSchedule>,
+}
+
+/// Work time per weekday in minutes for a range of days.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, Default)]
+#[allow(unused)]
+pub struct WorkSchedule {
+    /// First day of the schedule, open when not set.
+    #[serde(default)]
+    pub valid_from: Option<NaiveDate>,
+    /// Last day of the schedule, inclusive and open when not set.
+    #[serde(default)]
+    pub valid_to: Option<NaiveDate>,
+    #[serde(default)]
+    pub monday: u16,
+    #[serde(default)]
+    pub tuesday: u16,
+    #[serde(default)]
+    pub wednesday: u16,
+    #[serde(default)]
+    pub thursday: u16,
+    #[serde(default)]
+    pub friday: u16,
+    #[serde(default)]
+    pub saturday: u16,
+    #[serde(default)]
+    pub sunday: u16,
 }
 
 impl From<WorkPerDayInMinutes> for config::Value {
@@ -100,6 +215,15 @@ impl Default for Settings {
             threshold_limits: 1,
             limits: [].to_vec(),
             workperday: Default::default(),
+            balance: Default::default(),
+            absence: Default::default(),
+            holidays: Default::default(),
+            timezone: Default::default(),
+            rules: Default::default(),
+            rounding: Default::default(),
+            profiles: Default::default(),
+            profile: Default::default(),
+            paths: Default::default(),
         }
     }
 }
@@ -114,45 +238,97 @@ impl Default for WorkPerDayInMinutes {
             friday: 8 * 60,
             saturday: 0,
             sunday: 0,
+            holidays: Default::default(),
+            schedules: Default::default(),
         }
     }
 }
 
 impl Settings {
-    pub fn new() -> Result<Self, ConfigError> {
-        // env::set_var("RUST_TEST", "true")
-        let d = match env::var("RUST_TEST") {
-            Ok(_) => env::current_dir().unwrap(),
-            Err(_) => dirs::home_dir().unwrap(),
-        };
+    /// Settings of the profile in `TRACKRS_PROFILE`, the shared settings without one.
+    pub fn new() -> Result<Self, TrackerError> {
+        let paths = SettingsPaths::resolve(None)?;
+        Ok(Settings::load(&paths, env::var(PROFILE_ENV).ok().as_deref())?)
+    }
 
-        Settings::build(&d)
+    /// Settings of the configuration file, created if missing, with the profile applied.
+    ///
+    /// Fails with every error of [`SettingsCheck`], warnings are logged.
+    pub fn load(paths: &SettingsPaths, profile: Option<&str>) -> Result<Self, ConfigError> {
+        let mut settings = Settings::read(paths)?;
+        let check = SettingsCheck::from(&settings);
+        check.warnings().for_each(|w| log::warn!("{}", w));
+        if check.has_errors() {
+            let errors = check
+                .errors()
+                .map(|e| e.to_string())
+                .collect::<Vec<String>>();
+            return Err(ConfigError::Message(format!("invalid {}\n{}", settings.file, errors.join("\n"))));
+        }
+        if let Some(p) = profile {
+            settings.use_profile(p)?;
+        }
+        settings.read_holidays(paths.config.parent().unwrap_or(Path::new("")))?;
+        Ok(settings)
     }
 
-    fn build(base: &Path) -> Result<Self, ConfigError> {
+    /// Settings of the configuration file as they are, created if missing.
+    pub fn read(paths: &SettingsPaths) -> Result<Self, ConfigError> {
         let d = Settings::default();
-        let f = base.join(".trackrs");
-        Settings::assert_created(&f).unwrap();
+        let f = paths.config.to_str().unwrap();
+        Settings::assert_created(&paths.config).map_err(|e| ConfigError::Message(e.to_string()))?;
         let s = Config::builder()
-            .set_default("file", f.to_str().unwrap())?
+            .set_default("file", f)?
             .set_default("threshold_limits", d.threshold_limits)?
             .set_default("limits", d.limits)?
             .set_default("workperday", d.workperday)?
-            .add_source(File::new(f.to_str().unwrap(), FileFormat::Json))
+            .add_source(File::new(f, FileFormat::Json))
             .build()?;
         log::debug!("configuration: {:?}", s);
-        s.try_deserialize()
+        let mut settings: Settings = s.try_deserialize()?;
+        settings.paths = paths.to_owned();
+        Ok(settings)
+    }
+
+    /// Takeover file of the active profile.
+    pub fn takeover_file(&self) -> PathBuf {
+        self.paths.takeover(self.profile.as_deref())
+    }
+
+    fn use_profile(&mut self, name: &str) -> Result<(), ConfigError> {
+        let Some(profile) = self.profiles.get(name).cloned() else {
+            return Err(ConfigError::Message(format!("profile {} is not configured", name)));
+        };
+        log::debug!("use profile {}: {:?}", name, profile);
+        self.folder = profile.folder;
+        if let Some(l) = profile.limits {
+            self.limits = l;
+        }
+        if let Some(w) = profile.workperday {
+            self.workperday = w;
+        }
+        self.profile = Some(name.to_owned());
+        Ok(())
+    }
+
+    /// Read the holiday calendars, relative paths are based on the configuration folder.
+    fn read_holidays(&mut self, base: &Path) -> Result<(), ConfigError> {
+        for h in self.holidays.iter() {
+            let calendar = HolidayCalendar::builder()
+                .file(base.join(h))
+                .build()
+                .map_err(|e| ConfigError::Message(e.to_string()))?;
+            self.workperday.holidays.extend(calendar.days);
+        }
+        Ok(())
     }
 
     fn assert_created(file_path: &Path) -> Result<(), TrackerError> {
         if !file_path.exists() {
-            let w = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .append(false)
-                .truncate(false)
-                .open(file_path)?;
-            serde_json::to_writer_pretty(w, &Settings::required_fields())?;
+            if let Some(p) = file_path.parent() {
+                fs::create_dir_all(p)?;
+            }
+            write_json(file_path, &Settings::required_fields(), true)?;
         }
         Ok(())
     }
@@ -165,18 +341,17 @@ impl Settings {
 
 impl WorkPerDayInMinutes {
     pub fn from(&self, date: DateTime<Utc>) -> &u16 {
-        match date.weekday() {
-            chrono::Weekday::Mon => &self.monday,
-            chrono::Weekday::Tue => &self.tuesday,
-            chrono::Weekday::Wed => &self.wednesday,
-            chrono::Weekday::Thu => &self.thursday,
-            chrono::Weekday::Fri => &self.friday,
-            chrono::Weekday::Sat => &self.saturday,
-            chrono::Weekday::Sun => &self.sunday,
-        }
+        self.from_date(date)
     }
 
+    /// Work time of the weekday in the schedule of the date, unless the date is a holiday.
     pub fn from_date(&self, date: DateTime<Utc>) -> &u16 {
+        if let Some(h) = self.holidays.get(&date.date_naive()) {
+            return h;
+        }
+        if let Some(s) = self.schedules.iter().find(|s| s.contains(date.date_naive())) {
+            return s.minutes(date.weekday());
+        }
         match date.weekday() {
             chrono::Weekday::Mon => &self.monday,
             chrono::Weekday::Tue => &self.tuesday,
@@ -189,34 +364,86 @@ impl WorkPerDayInMinutes {
     }
 }
 
+impl WorkSchedule {
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.valid_from.is_none_or(|f| f <= date) && self.valid_to.is_none_or(|t| date <= t)
+    }
+
+    fn minutes(&self, weekday: Weekday) -> &u16 {
+        match weekday {
+            Weekday::Mon => &self.monday,
+            Weekday::Tue => &self.tuesday,
+            Weekday::Wed => &self.wednesday,
+            Weekday::Thu => &self.thursday,
+            Weekday::Fri => &self.friday,
+            Weekday::Sat => &self.saturday,
+            Weekday::Sun => &self.sunday,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use std::fs::OpenOptions;
+    use std::{ fs::{ self, OpenOptions }, path::Path };
+
+    use chrono::{ NaiveDate, TimeZone, Utc };
 
-    use crate::{ BreakLimit, Settings, TrackerError, WorkPerDayInMinutes };
+    use crate::{
+        AbsenceSettings,
+        BalanceSettings,
+        BreakLimit,
+        ProfileSettings,
+        Rounding,
+        RoundingMode,
+        RoundingSettings,
+        RuleSettings,
+        Settings,
+        SettingsPaths,
+        TrackerError,
+        WorkPerDayInMinutes,
+        WorkSchedule,
+        WorkTimeZone,
+    };
 
     mod settings {
         use super::*;
 
+        fn paths(dir: &Path) -> SettingsPaths {
+            SettingsPaths::file(dir.join(".trackrs"))
+        }
+
+        /// Configuration file in `dir`, with folders below `/temp` moved into it.
+        fn write_config(dir: &Path, json: &str) -> Result<(), TrackerError> {
+            fs::write(dir.join(".trackrs"), json.replace("/temp", dir.to_str().unwrap()))?;
+            Ok(())
+        }
+
         #[test]
         fn should_create() -> Result<(), TrackerError> {
             let temp_dir = tempfile::tempdir()?;
 
-            let settings = Settings::build(temp_dir.path()).unwrap();
+            let settings = Settings::load(&paths(temp_dir.path()), None).unwrap();
             assert!(&temp_dir.into_path().join(".trackrs").exists());
             assert_eq!(dirs::home_dir().unwrap().to_str().unwrap(), settings.folder);
             assert_eq!(1, settings.threshold_limits);
             assert_eq!(0, settings.limits.len());
             assert_eq!(8 * 60, settings.workperday.wednesday);
             assert_eq!(0, settings.workperday.saturday);
+            assert_eq!(BalanceSettings::default(), settings.balance);
+            assert_eq!(0, settings.absence.vacation);
+            assert_eq!(WorkTimeZone::default(), settings.timezone);
+            assert_eq!(RuleSettings::default(), settings.rules);
+            assert!(!settings.rounding.enabled());
             Ok(())
         }
 
         #[test]
         fn should_read() -> Result<(), TrackerError> {
+            let temp_dir = tempfile::tempdir()?;
+            let folder = |f: &str| temp_dir.path().join(f).to_str().unwrap().to_owned();
             let expected_settings = Settings {
                 file: "file".to_owned(),
-                folder: "/temp/dir".to_owned(),
+                folder: folder("dir"),
                 threshold_limits: 25,
                 limits: [
                     BreakLimit {
@@ -236,10 +463,27 @@ mod tests {
                     friday: 4 * 60,
                     saturday: 0,
                     sunday: 0,
+                    ..Default::default()
+                },
+                balance: BalanceSettings {
+                    start: -90,
+                    reset: NaiveDate::from_ymd_opt(2023, 1, 1),
                 },
+                absence: AbsenceSettings { vacation: 30 },
+                holidays: Default::default(),
+                timezone: chrono_tz::Europe::Berlin.into(),
+                rules: RuleSettings { min_break: 15, max_block: 6 * 60, max_worktime: 10 * 60, min_rest: 11 * 60 },
+                rounding: RoundingSettings {
+                    start: Rounding { minutes: 15, mode: RoundingMode::Up },
+                    ..Default::default()
+                },
+                profiles: [
+                    ("client".to_owned(), ProfileSettings { folder: folder("client"), ..Default::default() }),
+                ].into(),
+                profile: None,
+                paths: Default::default(),
             };
 
-            let temp_dir = tempfile::tempdir()?;
             let f = temp_dir.path().join(".trackrs");
 
             let w = OpenOptions::new()
@@ -250,13 +494,148 @@ mod tests {
                 .open(f)?;
             serde_json::to_writer(w, &expected_settings)?;
 
-            let settings = Settings::build(temp_dir.path()).unwrap();
+            let settings = Settings::load(&paths(temp_dir.path()), None).unwrap();
 
             assert_eq!(expected_settings.folder, settings.folder);
             assert_eq!(expected_settings.limits.len(), settings.limits.len());
             assert_eq!(expected_settings.threshold_limits, settings.threshold_limits);
             assert!(settings.limits.contains(&expected_settings.limits[0]));
             assert!(settings.limits.contains(&expected_settings.limits[1]));
+            assert_eq!(expected_settings.balance, settings.balance);
+            assert_eq!(expected_settings.absence, settings.absence);
+            assert_eq!(expected_settings.timezone, settings.timezone);
+            assert_eq!(expected_settings.rules, settings.rules);
+            assert_eq!(expected_settings.rounding, settings.rounding);
+            assert_eq!(expected_settings.profiles, settings.profiles);
+            Ok(())
+        }
+
+        #[test]
+        fn should_read_holidays() -> Result<(), TrackerError> {
+            let temp_dir = tempfile::tempdir()?;
+            fs::write(
+                temp_dir.path().join("holidays.json"),
+                r#"["2022-12-26", {"date": "2022-12-23", "minutes": 240}]"#
+            )?;
+            write_config(temp_dir.path(), r#"{"folder": "/temp/dir", "holidays": ["holidays.json"]}"#)?;
+
+            let settings = Settings::load(&paths(temp_dir.path()), None).unwrap();
+            let date = |d: u32| Utc.with_ymd_and_hms(2022, 12, d, 0, 0, 0).unwrap();
+
+            assert_eq!(&0, settings.workperday.from_date(date(26)));
+            assert_eq!(&240, settings.workperday.from_date(date(23)));
+            assert_eq!(&(8 * 60), settings.workperday.from_date(date(22)));
+            Ok(())
+        }
+
+        #[test]
+        fn should_read_schedules() -> Result<(), TrackerError> {
+            let temp_dir = tempfile::tempdir()?;
+            write_config(
+                temp_dir.path(),
+                r#"{
+                    "folder": "/temp/dir",
+                    "workperday": {
+                        "friday": 360,
+                        "schedules": [
+                            {"valid_to": "2023-02-28", "monday": 480, "friday": 480},
+                            {"valid_from": "2023-03-01", "monday": 240}
+                        ]
+                    }
+                }"#
+            )?;
+
+            let settings = Settings::load(&paths(temp_dir.path()), None).unwrap();
+            let date = |m: u32, d: u32| Utc.with_ymd_and_hms(2023, m, d, 0, 0, 0).unwrap();
+
+            assert_eq!(2, settings.workperday.schedules.len());
+            assert_eq!(&(8 * 60), settings.workperday.from_date(date(2, 24)));
+            assert_eq!(&(8 * 60), settings.workperday.from(date(2, 27)));
+            assert_eq!(&0, settings.workperday.from_date(date(2, 28)));
+            assert_eq!(&(4 * 60), settings.workperday.from_date(date(3, 6)));
+            assert_eq!(&0, settings.workperday.from_date(date(3, 3)));
+            Ok(())
+        }
+
+        #[test]
+        fn should_resolve_schedule() {
+            let date = |m: u32, d: u32| Utc.with_ymd_and_hms(2023, m, d, 0, 0, 0).unwrap();
+            let workperday = WorkPerDayInMinutes {
+                holidays: [(date(4, 7).date_naive(), 0)].into(),
+                schedules: vec![WorkSchedule {
+                    valid_from: NaiveDate::from_ymd_opt(2023, 4, 1),
+                    valid_to: NaiveDate::from_ymd_opt(2023, 4, 30),
+                    friday: 5 * 60,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            };
+
+            assert_eq!(&(8 * 60), workperday.from_date(date(3, 31)));
+            assert_eq!(&(5 * 60), workperday.from_date(date(4, 14)));
+            assert_eq!(&0, workperday.from_date(date(4, 7)));
+            assert_eq!(&(5 * 60), workperday.from_date(date(4, 28)));
+            assert_eq!(&(8 * 60), workperday.from_date(date(5, 5)));
+        }
+
+        #[test]
+        fn should_use_profile() -> Result<(), TrackerError> {
+            let temp_dir = tempfile::tempdir()?;
+            write_config(
+                temp_dir.path(),
+                r#"{
+                    "folder": "/temp/dir",
+                    "limits": [{"start": 360, "minutes": 30}],
+                    "profiles": {
+                        "client": {
+                            "folder": "/temp/client",
+                            "workperday": {
+                                "monday": 240, "tuesday": 240, "wednesday": 0, "thursday": 0,
+                                "friday": 0, "saturday": 0, "sunday": 0
+                            }
+                        }
+                    }
+                }"#
+            )?;
+
+            let shared = Settings::load(&paths(temp_dir.path()), None).unwrap();
+            assert_eq!(temp_dir.path().join("dir").to_str().unwrap(), shared.folder);
+            assert_eq!(None, shared.profile);
+            assert_eq!(8 * 60, shared.workperday.monday);
+
+            let settings = Settings::load(&paths(temp_dir.path()), Some("client")).unwrap();
+            assert_eq!(temp_dir.path().join("client").to_str().unwrap(), settings.folder);
+            assert_eq!(Some("client".to_owned()), settings.profile);
+            assert_eq!(4 * 60, settings.workperday.monday);
+            assert_eq!(0, settings.workperday.wednesday);
+            assert_eq!(shared.limits, settings.limits);
+            assert_eq!(temp_dir.path().join("takeover.json"), shared.takeover_file());
+            assert_eq!(temp_dir.path().join("takeover-client.json"), settings.takeover_file());
+
+            assert!(Settings::load(&paths(temp_dir.path()), Some("none")).is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_load_invalid() -> Result<(), TrackerError> {
+            let temp_dir = tempfile::tempdir()?;
+            write_config(
+                temp_dir.path(),
+                r#"{"folder": "/temp/dir", "workperday": {"monday": 1500, "tuesday": 480, "wednesday": 480,
+                    "thursday": 480, "friday": 480, "saturday": 0, "sunday": 0}}"#
+            )?;
+
+            let error = Settings::load(&paths(temp_dir.path()), None).unwrap_err().to_string();
+            assert!(error.contains("workperday.monday: 1500 minutes are more than 24 hours"), "{}", error);
+            assert_eq!(1500, Settings::read(&paths(temp_dir.path())).unwrap().workperday.monday);
+            Ok(())
+        }
+
+        #[test]
+        fn missing_holidays() -> Result<(), TrackerError> {
+            let temp_dir = tempfile::tempdir()?;
+            write_config(temp_dir.path(), r#"{"folder": "/temp/dir", "holidays": ["none.ics"]}"#)?;
+            assert!(Settings::load(&paths(temp_dir.path()), None).is_err());
             Ok(())
         }
     }
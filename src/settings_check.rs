@@ -0,0 +1,292 @@
+use std::{ fmt::Display, fs::{ self, File }, path::{ Path, PathBuf }, process };
+
+use colored::Colorize;
+
+use crate::{ BreakLimit, Settings, WorkPerDayInMinutes, WorkSchedule };
+
+/// Longest work time of a day in minutes.
+const MAX_WORKPERDAY: u16 = 24 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SettingsSeverity {
+    /// The settings work, but likely not as intended.
+    Warning,
+    /// The settings can not be used.
+    Error,
+}
+
+/// Problem of a setting, `field` is its path in the configuration file, eg. `limits[1].start`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SettingsProblem {
+    pub severity: SettingsSeverity,
+    pub field: String,
+    pub message: String,
+}
+
+/// Problems of the shared settings and of every profile.
+#[derive(Clone, Debug, Default)]
+pub struct SettingsCheck {
+    pub problems: Vec<SettingsProblem>,
+}
+
+impl SettingsCheck {
+    pub fn has_errors(&self) -> bool {
+        self.problems.iter().any(|p| p.severity == SettingsSeverity::Error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &SettingsProblem> {
+        self.problems.iter().filter(|p| p.severity == SettingsSeverity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &SettingsProblem> {
+        self.problems.iter().filter(|p| p.severity == SettingsSeverity::Warning)
+    }
+
+    fn problem(&mut self, severity: SettingsSeverity, field: String, message: String) {
+        log::debug!("{:?} in {}: {}", severity, field, message);
+        self.problems.push(SettingsProblem { severity, field, message });
+    }
+
+    fn limits(&mut self, path: &str, limits: &[BreakLimit], threshold: u8) {
+        for (i, w) in limits.windows(2).enumerate() {
+            if w[1].start < w[0].start {
+                self.problem(
+                    SettingsSeverity::Warning,
+                    format!("{}[{}].start", path, i + 1),
+                    format!("{} is before {} of {}[{}], limits should be sorted by start", w[1].start, w[0].start, path, i)
+                );
+            }
+        }
+
+        let mut sorted = limits.iter().enumerate().collect::<Vec<(usize, &BreakLimit)>>();
+        sorted.sort_by_key(|(_, l)| l.start);
+        for w in sorted.windows(2) {
+            let ((i, a), (j, b)) = (w[0], w[1]);
+            let gap = b.start - a.start;
+            if gap < a.minutes.into() {
+                self.problem(
+                    SettingsSeverity::Error,
+                    format!("{}[{}].start", path, j),
+                    format!(
+                        "{} overlaps the break of {}[{}] from {} to {}",
+                        b.start,
+                        path,
+                        i,
+                        a.start,
+                        a.start + u16::from(a.minutes)
+                    )
+                );
+            }
+            if u16::from(threshold) > gap {
+                self.problem(
+                    SettingsSeverity::Error,
+                    "threshold_limits".to_owned(),
+                    format!("{} is larger than the gap of {} between {}[{}] and {}[{}]", threshold, gap, path, i, path, j)
+                );
+            }
+        }
+    }
+
+    fn workperday(&mut self, path: &str, workperday: &WorkPerDayInMinutes) {
+        let days = [
+            ("monday", workperday.monday),
+            ("tuesday", workperday.tuesday),
+            ("wednesday", workperday.wednesday),
+            ("thursday", workperday.thursday),
+            ("friday", workperday.friday),
+            ("saturday", workperday.saturday),
+            ("sunday", workperday.sunday),
+        ];
+        self.days(path, &days);
+        for (i, s) in workperday.schedules.iter().enumerate() {
+            self.days(&format!("{}.schedules[{}]", path, i), &SettingsCheck::schedule_days(s));
+        }
+    }
+
+    fn schedule_days(s: &WorkSchedule) -> [(&'static str, u16); 7] {
+        [
+            ("monday", s.monday),
+            ("tuesday", s.tuesday),
+            ("wednesday", s.wednesday),
+            ("thursday", s.thursday),
+            ("friday", s.friday),
+            ("saturday", s.saturday),
+            ("sunday", s.sunday),
+        ]
+    }
+
+    fn days(&mut self, path: &str, days: &[(&str, u16)]) {
+        for (day, minutes) in days.iter().filter(|(_, m)| *m > MAX_WORKPERDAY) {
+            self.problem(
+                SettingsSeverity::Error,
+                format!("{}.{}", path, day),
+                format!("{} minutes are more than 24 hours", minutes)
+            );
+        }
+    }
+
+    fn folder(&mut self, path: &str, folder: &str) {
+        if folder.is_empty() {
+            self.problem(SettingsSeverity::Error, path.to_owned(), "is empty".to_owned());
+        } else if let Err(message) = SettingsCheck::writable(Path::new(folder)) {
+            self.problem(SettingsSeverity::Error, path.to_owned(), message);
+        }
+    }
+
+    /// Write a file into the folder, or the nearest existing parent the folder is created in.
+    fn writable(folder: &Path) -> Result<(), String> {
+        let mut existing = folder.to_path_buf();
+        while !existing.exists() {
+            match existing.parent() {
+                Some(p) if !p.as_os_str().is_empty() => {
+                    existing = p.to_path_buf();
+                }
+                _ => {
+                    existing = PathBuf::from(".");
+                    break;
+                }
+            }
+        }
+        if !existing.is_dir() {
+            return Err(format!("{} is not a folder", existing.display()));
+        }
+        let probe = existing.join(format!(".trackrs-check.{}", process::id()));
+        File::create(&probe)
+            .and_then(|_| fs::remove_file(&probe))
+            .map_err(|e| format!("{} is not writable: {}", existing.display(), e))
+    }
+}
+
+impl From<&Settings> for SettingsCheck {
+    fn from(settings: &Settings) -> Self {
+        let mut check = SettingsCheck::default();
+        check.folder("folder", &settings.folder);
+        check.limits("limits", &settings.limits, settings.threshold_limits);
+        check.workperday("workperday", &settings.workperday);
+        for (name, p) in settings.profiles.iter() {
+            let path = format!("profiles.{}", name);
+            check.folder(&format!("{}.folder", path), &p.folder);
+            if let Some(l) = p.limits.as_ref() {
+                check.limits(&format!("{}.limits", path), l, settings.threshold_limits);
+            }
+            if let Some(w) = p.workperday.as_ref() {
+                check.workperday(&format!("{}.workperday", path), w);
+            }
+        }
+        check
+    }
+}
+
+impl Display for SettingsProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+impl Display for SettingsCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.problems
+            .iter()
+            .map(|p| match p.severity {
+                SettingsSeverity::Warning => format!("{:width$}{}", "Warning:", p, width = 13).bright_yellow(),
+                SettingsSeverity::Error => format!("{:width$}{}", "Error:", p, width = 13).bright_red(),
+            })
+            .map(|l| l.to_string())
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ ProfileSettings, WorkSchedule };
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn problems(check: &SettingsCheck) -> Vec<(SettingsSeverity, String)> {
+        check.problems
+            .iter()
+            .map(|p| (p.severity, p.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn should_pass() -> Result<(), std::io::Error> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let settings = Settings {
+            folder: temp_dir.path().join("trackrs").to_str().unwrap().to_owned(),
+            threshold_limits: 5,
+            limits: vec![BreakLimit { start: 361, minutes: 30 }, BreakLimit { start: 481, minutes: 45 }],
+            ..Default::default()
+        };
+        let check = SettingsCheck::from(&settings);
+        assert!(check.problems.is_empty(), "{}", check);
+        assert!(!temp_dir.path().join("trackrs").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_limits() -> Result<(), std::io::Error> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let settings = Settings {
+            folder: temp_dir.path().to_str().unwrap().to_owned(),
+            threshold_limits: 30,
+            limits: vec![
+                BreakLimit { start: 540, minutes: 45 },
+                BreakLimit { start: 360, minutes: 30 },
+                BreakLimit { start: 380, minutes: 30 }
+            ],
+            ..Default::default()
+        };
+        let check = SettingsCheck::from(&settings);
+        assert_eq!(
+            vec![
+                (SettingsSeverity::Warning, "limits[1].start: 360 is before 540 of limits[0], limits should be sorted by start"
+                    .to_owned()),
+                (SettingsSeverity::Error, "limits[2].start: 380 overlaps the break of limits[1] from 360 to 390".to_owned()),
+                (SettingsSeverity::Error, "threshold_limits: 30 is larger than the gap of 20 between limits[1] and limits[2]"
+                    .to_owned())
+            ],
+            problems(&check)
+        );
+        assert!(check.has_errors());
+        assert_eq!(1, check.warnings().count());
+        Ok(())
+    }
+
+    #[test]
+    fn should_report_workperday_and_folder() -> Result<(), std::io::Error> {
+        logger();
+        let temp_dir = tempfile::tempdir()?;
+        let file = temp_dir.path().join("file");
+        fs::write(&file, "")?;
+        let settings = Settings {
+            folder: file.join("trackrs").to_str().unwrap().to_owned(),
+            workperday: WorkPerDayInMinutes {
+                friday: 1500,
+                schedules: vec![WorkSchedule { sunday: 1441, ..Default::default() }],
+                ..Default::default()
+            },
+            profiles: [("client".to_owned(), ProfileSettings { folder: "".to_owned(), ..Default::default() })].into(),
+            ..Default::default()
+        };
+        let check = SettingsCheck::from(&settings);
+        assert_eq!(
+            vec![
+                format!("folder: {} is not a folder", file.display()),
+                "workperday.friday: 1500 minutes are more than 24 hours".to_owned(),
+                "workperday.schedules[0].sunday: 1441 minutes are more than 24 hours".to_owned(),
+                "profiles.client.folder: is empty".to_owned()
+            ],
+            check.errors().map(|p| p.to_string()).collect::<Vec<String>>()
+        );
+        Ok(())
+    }
+}
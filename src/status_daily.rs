@@ -1,9 +1,20 @@
-use std::ops::{ Add, Mul };
+use std::{ collections::BTreeMap, ops::{ Add, Mul } };
 
-use chrono::{ DateTime, Duration, Local, Utc };
+use chrono::{ DateTime, Duration, Utc };
 use colored::Colorize;
 
-use crate::{ Settings, Status, StatusTime, TimeData, TrackerError };
+use crate::{
+    NoteOutput,
+    ProjectOutput,
+    Rounding,
+    Settings,
+    Status,
+    StatusDailyOutput,
+    StatusTime,
+    TimeData,
+    TrackerError,
+    WorkTimeZone,
+};
 
 #[derive(Default, Clone, Debug)]
 pub struct StatusDaily {
@@ -12,8 +23,11 @@ pub struct StatusDaily {
     pub start: Option<StatusTime>,
     pub end: Option<StatusTime>,
     temp_end: Option<StatusTime>,
+    /// time of start and end, to correct wall clock times on daylight saving time switches.
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
     /// first break of day
-    f_break: Option<DateTime<Local>>,
+    f_break: Option<DateTime<Utc>>,
     /// calculated break. whichever is higher break or exp_break.
     calc_break: Option<StatusTime>,
     pub r#break: Option<StatusTime>,
@@ -21,20 +35,113 @@ pub struct StatusDaily {
 
     online: Option<StatusTime>,
     est_end: StatusTime,
+    est_end_time: Option<DateTime<Utc>>,
 
     pub worktime: StatusTime,
+    /// start, end and worktime after the configured rounding, equal to the tracked ones without rounding.
+    pub rounded_start: Option<StatusTime>,
+    pub rounded_end: Option<StatusTime>,
+    pub rounded_worktime: StatusTime,
     /// expected worktime for that day.
     exp_worktime: Option<StatusTime>,
     pub overtime: StatusTime,
+    /// tracked time per project, `None` for segments without project.
+    pub projects: BTreeMap<Option<String>, StatusTime>,
+    /// note of the day.
+    pub note: Option<String>,
+    /// notes of segments with their start.
+    pub notes: Vec<(StatusTime, String)>,
 }
 
 impl StatusDaily {
+    /// Day note and segment notes in a single line.
+    pub fn notes_line(&self) -> String {
+        self.note
+            .iter()
+            .cloned()
+            .chain(self.notes.iter().map(|(t, n)| format!("{} {}", t.clock(), n)))
+            .collect::<Vec<String>>()
+            .join("; ")
+    }
+
+    pub fn rounding_enabled(&self) -> bool {
+        self.settings.as_ref().is_some_and(|s| s.rounding.enabled())
+    }
+
+    /// Status in the schema of the JSON and YAML output.
+    pub fn output(&self) -> StatusDailyOutput {
+        let zone = self.zone();
+        let minutes = |t: &Option<StatusTime>| t.as_ref().map(|t| t.duration.num_minutes()).unwrap_or_default();
+        let data = self.data.as_ref();
+        let worktime = self.worktime.duration.num_minutes();
+        let expected = minutes(&self.exp_worktime);
+        // rounded times are shifted by the rounding of their wall clock time
+        let rounded = |time: Option<DateTime<Utc>>, raw: &Option<StatusTime>, rounded: &Option<StatusTime>| {
+            match (time, raw, rounded) {
+                (Some(t), Some(a), Some(b)) => Some(zone.fixed(t + (b.duration - a.duration))),
+                _ => None,
+            }
+        };
+        let notes = data
+            .map(|d| {
+                d.entries.data
+                    .iter()
+                    .filter_map(|e| {
+                        e.note.as_ref().map(|n| NoteOutput { time: zone.fixed(e.time), note: n.to_owned() })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        StatusDailyOutput {
+            date: data.and_then(|d| d.date).map(|d| d.date_naive()),
+            absence: data.and_then(|d| d.absence),
+            start: self.start_time.map(|t| zone.fixed(t)),
+            // a running session has a temporary end time
+            end: self.end.as_ref().and(self.end_time).map(|t| zone.fixed(t)),
+            est_end: self.est_end_time.map(|t| zone.fixed(t)),
+            rounded_start: rounded(self.start_time, &self.start, &self.rounded_start),
+            rounded_end: rounded(self.end.as_ref().and(self.end_time), &self.end, &self.rounded_end),
+            r#break: minutes(&self.r#break),
+            exp_break: minutes(&self.exp_break),
+            online: minutes(&self.online),
+            worktime,
+            rounded_worktime: self.rounded_worktime.duration.num_minutes(),
+            expected,
+            overtime: worktime - expected,
+            projects: self.projects.iter().map(ProjectOutput::from).collect(),
+            note: self.note.to_owned(),
+            notes,
+        }
+    }
+
     pub fn builder() -> StatusDailyBuilder {
         StatusDailyBuilder {
             inner: StatusDaily::default(),
         }
     }
 
+    fn zone(&self) -> WorkTimeZone {
+        self.settings.as_ref().map(|s| s.timezone).unwrap_or_default()
+    }
+
+    /// Time of day relative to the start day, times of a session past midnight exceed 24 hours.
+    fn clock(&self, time: DateTime<Utc>) -> StatusTime {
+        let zone = self.zone();
+        let days = match self.start_time {
+            Some(s) => (zone.date(time) - zone.date(s)).num_days(),
+            None => 0,
+        };
+        StatusTime::at(time, &zone) + Duration::days(days).into()
+    }
+
+    /// Change of the utc offset between the start of the day and `time`.
+    fn offset_change(&self, time: Option<DateTime<Utc>>) -> Duration {
+        match (self.start_time, time) {
+            (Some(s), Some(t)) => self.zone().offset(t) - self.zone().offset(s),
+            _ => Duration::zero(),
+        }
+    }
+
     fn has_connect(&self) -> bool {
         log::debug!("check if any connect entry is present");
         match self.data.as_ref() {
@@ -53,7 +160,8 @@ impl StatusDaily {
         {
             Some(c) => {
                 log::info!("connect at: {}", c.time.time());
-                Some(c.into())
+                self.start_time = Some(c.time);
+                Some(StatusTime::entry(c, &self.zone()))
             }
             None => {
                 log::error!("connect entry not found in time data");
@@ -75,11 +183,19 @@ impl StatusDaily {
             Some(c) => {
                 log::info!("end at: {}", c.time.time());
                 log::info!("finished reading time data for {}", c.time.date_naive());
-                self.end = Some(c.into());
+                self.end_time = Some(c.time);
+                self.end = Some(self.clock(c.time));
             }
             None => {
                 log::debug!("no end entry found threrefore create a temporary one");
-                self.temp_end = Some(StatusTime::now());
+                let now = Utc::now();
+                // an open day of the past has no meaningful end, keep its wall clock times
+                if self.data.as_ref().unwrap().continues_at(now) {
+                    self.end_time = Some(now);
+                    self.temp_end = Some(self.clock(now));
+                } else {
+                    self.temp_end = Some(StatusTime::at(now, &self.zone()));
+                }
             }
         }
         self
@@ -105,8 +221,7 @@ impl StatusDaily {
                     log::info!("break at: {}", tb.time());
                     b = true;
                     if !f {
-                        let local_f_break: DateTime<Local> = DateTime::from(d.entries.data[n].time);
-                        self.f_break = Some(local_f_break);
+                        self.f_break = Some(d.entries.data[n].time);
                         f = true;
                     }
                 }
@@ -117,7 +232,7 @@ impl StatusDaily {
                 // caluclate time between both
                 let tbd = tc - tb;
                 // add to general break
-                break_duration = break_duration + tbd;
+                break_duration += tbd;
                 b = false;
             }
         }
@@ -127,18 +242,13 @@ impl StatusDaily {
     }
 
     fn set_exp_break(&mut self) -> &mut Self {
-        if self.online.is_none() {
-            log::error!("online time not yet calculated");
-        } else {
+        if let Some(o) = self.online.as_ref() {
             let d = self.data.as_ref().unwrap();
             let s = self.settings.as_ref().unwrap();
-            // get work per day as based on the first entry of time data
-            let w = Duration::minutes(s.workperday.from(d.entries.data[0].time).to_owned().into());
+            let w = Duration::minutes(d.expected(s).into());
 
             self.exp_worktime = Some(StatusTime::from(w));
 
-            let o = self.online.as_ref().unwrap();
-
             // get whatever time is heigher, either expected working time for the day or the online time.
             let (tft, t) = if o >= &StatusTime::from(w) {
                 (o.to_owned().duration, Duration::minutes(s.threshold_limits.to_owned().into()))
@@ -161,20 +271,18 @@ impl StatusDaily {
                     Some(Duration::minutes(0).into())
                 }
             };
+        } else {
+            log::error!("online time not yet calculated");
         }
         self
     }
 
     fn set_calc_break(&mut self) -> &mut Self {
-        if self.r#break.is_none() || self.exp_break.is_none() {
-            log::error!("break times are not set");
-        } else {
-            let r#break = if self.r#break.as_ref().unwrap() >= self.exp_break.as_ref().unwrap() {
-                self.r#break.as_ref().unwrap().to_owned()
-            } else {
-                self.exp_break.as_ref().unwrap().to_owned()
-            };
+        if let (Some(b), Some(eb)) = (self.r#break.as_ref(), self.exp_break.as_ref()) {
+            let r#break = if b >= eb { b.to_owned() } else { eb.to_owned() };
             self.calc_break = Some(r#break);
+        } else {
+            log::error!("break times are not set");
         }
         self
     }
@@ -188,7 +296,8 @@ impl StatusDaily {
         if self.start.is_none() {
             log::error!("start time is not set");
         } else {
-            self.online = Some(end - self.start.to_owned().unwrap());
+            let online = end - self.start.to_owned().unwrap();
+            self.online = Some(online - self.offset_change(self.end_time).into());
         }
         self
     }
@@ -200,15 +309,16 @@ impl StatusDaily {
             // set expected end time
             let d = self.data.as_ref().unwrap();
             let s = self.settings.as_ref().unwrap();
-            // get work per day as based on the first entry of time data
-            let w = Duration::minutes(s.workperday.from(d.entries.data[0].time).to_owned().into());
+            let w = Duration::minutes(d.expected(s).into());
 
             let e = if self.r#break.to_owned().unwrap() > self.exp_break.to_owned().unwrap() {
                 w.add(self.r#break.to_owned().unwrap().into())
             } else {
                 w.add(self.exp_break.to_owned().unwrap().into())
             };
-            self.est_end = self.start.to_owned().unwrap().add(e.into());
+            let change = self.offset_change(self.start_time.map(|s| s + e));
+            self.est_end = self.start.to_owned().unwrap().add(e.add(change).into());
+            self.est_end_time = self.start_time.map(|s| s + e);
         }
         self
     }
@@ -219,6 +329,45 @@ impl StatusDaily {
         self
     }
 
+    fn set_rounded(&mut self) -> &mut Self {
+        let rounding = self.settings.as_ref().unwrap().rounding.to_owned();
+        let round = |r: &Rounding, t: &StatusTime| StatusTime::from(r.apply(t.duration));
+        self.rounded_start = self.start.as_ref().map(|s| round(&rounding.start, s));
+        self.rounded_end = self.end.as_ref().map(|e| round(&rounding.end, e));
+        let end = self.rounded_end.to_owned().or(self.temp_end.as_ref().map(|e| round(&rounding.end, e)));
+        // without a calculated break the rounded work time stays unset, like the work time
+        if let (Some(s), Some(e), Some(b)) = (self.rounded_start.to_owned(), end, self.calc_break.to_owned()) {
+            let online = e - s - self.offset_change(self.end_time).into();
+            let worktime = online - b;
+            self.rounded_worktime = rounding.worktime.apply(worktime.duration).into();
+        }
+        self
+    }
+
+    fn set_projects(&mut self) -> &mut Self {
+        let d = self.data.as_ref().unwrap();
+        let end = match d.entries.data.iter().find(|x| x.status == Status::End) {
+            Some(e) => e.time,
+            None => Utc::now(),
+        };
+        self.projects = d.entries
+            .projects(end)
+            .into_iter()
+            .map(|(p, t)| (p, StatusTime::from(t)))
+            .collect();
+        self
+    }
+
+    fn set_notes(&mut self) -> &mut Self {
+        let d = self.data.as_ref().unwrap();
+        self.note = d.entries.note.to_owned();
+        self.notes = d.entries.data
+            .iter()
+            .filter_map(|e| e.note.as_ref().map(|n| (self.clock(e.time), n.to_owned())))
+            .collect();
+        self
+    }
+
     fn set_overtime(&mut self) -> &mut Self {
         self.overtime = self.worktime.to_owned() - self.exp_worktime.as_ref().unwrap().to_owned();
         self
@@ -269,13 +418,23 @@ impl StatusDailyBuilder {
             .set_calc_break()
             .set_est_end()
             .set_worktime()
-            .set_overtime();
+            .set_rounded()
+            .set_overtime()
+            .set_projects()
+            .set_notes();
         Ok(d)
     }
 
+    /// Expected work time of a day without entries.
+    pub fn expected(&mut self, expected: StatusTime) -> &mut Self {
+        self.inner.exp_worktime = Some(expected);
+        self
+    }
+
     pub fn empty_with_overtime(&mut self, overtime: StatusTime) -> StatusDaily {
         StatusDaily {
             overtime,
+            exp_worktime: self.inner.exp_worktime.to_owned(),
             ..Default::default()
         }
     }
@@ -308,30 +467,23 @@ impl std::fmt::Display for StatusDaily {
 
         let mut fmt_break_report = "".to_owned();
 
-        let end_fmt = if end.is_some() {
-            let f_break = self.f_break.map(StatusTime::from);
-
-            if f_break.is_some() {
-                let e_break = StatusTime::from(
-                    self.f_break.unwrap().add(self.r#break.to_owned().unwrap().duration)
-                );
+        let end_fmt = if let Some(end) = end {
+            if let Some(f_break) = self.f_break {
+                let e_break = self.clock(f_break.add(self.r#break.to_owned().unwrap().duration));
                 fmt_break_report = format!(
                     "\n{:width$}{} - {}",
                     "Break taken:",
-                    f_break.unwrap(),
-                    e_break,
+                    self.clock(f_break).clock(),
+                    e_break.clock(),
                     width = 13
                 );
             }
 
-            format!("{}", end.unwrap()).bright_green()
-        } else if temp_end.is_some() && temp_end.as_ref().unwrap() >= &self.est_end {
-            format!("{}", temp_end.unwrap()).bright_green()
+            end.clock().bright_green()
+        } else if let Some(temp_end) = temp_end.filter(|x| x >= &self.est_end) {
+            temp_end.clock().bright_green()
         } else {
-            let hours = self.est_end.hours % 24;
-            // This hack is required because in the relative time is know in the current context.
-            // A time format like 25:15 doesn't make sense here, whereas 01:15 is understandable in this context.
-            format!("{:0>2}:{:0>2} (est.)", hours, self.est_end.minutes).bright_yellow()
+            format!("{} (est.)", self.est_end.clock()).bright_yellow()
         };
 
         let line1 = format!("{:width$}{} ({})", "Work time:", worktime, rm_fmt, width = 13);
@@ -345,7 +497,38 @@ impl std::fmt::Display for StatusDaily {
         let line4 = fmt_break_report;
         let line5 = format!("{:width$}{}", "Started:", start, width = 13);
         let line6 = format!("{:width$}{}", "End:", end_fmt, width = 13);
-        write!(f, "{}\n{}\n{}\n{}\n{}\n{}", line1, line2, line3, line4, line5, line6)
+        write!(f, "{}\n{}\n{}\n{}\n{}\n{}", line1, line2, line3, line4, line5, line6)?;
+
+        if self.rounding_enabled() {
+            let span = match (self.rounded_start.as_ref(), self.rounded_end.as_ref()) {
+                (Some(s), Some(e)) => format!("{} - {}", s.clock(), e.clock()),
+                (Some(s), None) => format!("since {}", s.clock()),
+                _ => String::new(),
+            };
+            write!(f, "\n{:width$}{} ({})", "Rounded:", self.rounded_worktime, span, width = 13)?;
+        }
+
+        // breakdown is only of interest when projects are tracked
+        if self.projects.keys().any(|p| p.is_some()) {
+            writeln!(f)?;
+            for (n, (p, t)) in self.projects.iter().enumerate() {
+                let label = if n == 0 { "Projects:" } else { "" };
+                let name = p.as_deref().unwrap_or("-");
+                write!(f, "\n{:width$}{:name_width$}{}", label, name, t, width = 13, name_width = 16)?;
+            }
+        }
+
+        if self.note.is_some() || !self.notes.is_empty() {
+            writeln!(f)?;
+        }
+        if let Some(n) = self.note.as_ref() {
+            write!(f, "\n{:width$}{}", "Note:", n, width = 13)?;
+        }
+        for (n, (t, note)) in self.notes.iter().enumerate() {
+            let label = if n == 0 { "Notes:" } else { "" };
+            write!(f, "\n{:width$}{} {}", label, t.clock(), note, width = 13)?;
+        }
+        Ok(())
     }
 }
 
@@ -395,6 +578,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -402,6 +586,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 3, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -409,6 +594,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 23, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -416,6 +602,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 14, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -477,6 +664,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -484,6 +672,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 3, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -491,6 +680,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 43, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -498,6 +688,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 17, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -547,6 +738,60 @@ mod tests {
             }
         }
 
+        #[test]
+        fn status_daily_with_projects_and_notes() {
+            logger();
+            let data = TimeData {
+                entries: {
+                    Entries {
+                        data: [
+                            Entry {
+                                id: 1,
+                                status: Status::Connect,
+                                time: Local.with_ymd_and_hms(2022, 2, 2, 8, 0, 0).unwrap().to_utc(),
+                                project: Some("foo".to_owned()),
+                                note: Some("planning".to_owned()),
+                                ..Default::default()
+                            },
+                            Entry {
+                                id: 2,
+                                status: Status::Connect,
+                                time: Local.with_ymd_and_hms(2022, 2, 2, 10, 0, 0).unwrap().to_utc(),
+                                project: Some("bar".to_owned()),
+                                ..Default::default()
+                            },
+                            Entry {
+                                id: 3,
+                                status: Status::End,
+                                time: Local.with_ymd_and_hms(2022, 2, 2, 14, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
+                            },
+                        ].to_vec(),
+                        note: Some("release".to_owned()),
+                        ..Default::default()
+                    }
+                },
+                ..Default::default()
+            };
+
+            let status = StatusDaily::builder().data(data).settings(Settings::default()).build().unwrap();
+
+            assert_eq!(StatusTime::from(Duration::hours(4)), status.projects[&Some("bar".to_owned())]);
+            assert_eq!("release; 08:00 planning", status.notes_line());
+            assert!(
+                format!("{}", status).ends_with(
+                    indoc!(
+                        "
+                        Projects:    bar             04:00
+                                     foo             02:00
+
+                        Note:        release
+                        Notes:       08:00 planning"
+                    )
+                )
+            );
+        }
+
         #[test]
         fn status_daily_on_point() {
             logger();
@@ -558,6 +803,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -565,6 +811,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 3, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -572,6 +819,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 33, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -579,6 +827,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 16, 33, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -644,6 +893,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: local.to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -720,6 +970,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 22, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -727,6 +978,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 16, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -793,6 +1045,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 22, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -800,6 +1053,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 16, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -807,6 +1061,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 46, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -878,6 +1133,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -885,6 +1141,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 15, 6, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -911,6 +1168,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Disconnect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -934,6 +1192,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 10, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -941,6 +1200,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 10, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -948,6 +1208,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 10, 10, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -976,6 +1237,7 @@ mod tests {
                                 id: 2,
                                 status: Status::Connect,
                                 time: DateTime::default(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1007,6 +1269,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 10, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1014,6 +1277,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 40, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1021,6 +1285,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1052,6 +1317,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 10, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1059,6 +1325,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 40, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1066,6 +1333,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 40, 5)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -1073,6 +1341,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1080,6 +1349,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 9, 40, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 6,
@@ -1087,6 +1357,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 9, 40, 55)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 7,
@@ -1094,6 +1365,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 9, 55, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 8,
@@ -1101,6 +1373,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 10, 0, 55)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 9,
@@ -1108,6 +1381,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 10, 0, 56)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 10,
@@ -1115,6 +1389,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 10, 1, 56)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1144,6 +1419,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1151,6 +1427,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 15, 6, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1176,16 +1453,19 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
                                 status: Status::Break,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
                                 status: Status::Disconnect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 0, 1).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -1193,6 +1473,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1200,6 +1481,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 5, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1246,16 +1528,19 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 0, 00, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
                                 status: Status::Break,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
                                 status: Status::Disconnect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 0, 1).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -1263,6 +1548,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 15, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1270,6 +1556,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 5, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1318,21 +1605,25 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 0, 00, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
                                 status: Status::Break,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
                                 status: Status::Disconnect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 3, 0, 1).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 4, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1340,6 +1631,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 5, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1387,6 +1679,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 10, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1394,6 +1687,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 13, 0, 1)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -1401,6 +1695,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 13, 15, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1408,6 +1703,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 15, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1457,6 +1753,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 10, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1464,6 +1761,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 13, 0, 1)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -1471,6 +1769,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 13, 20, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1478,6 +1777,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 15, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1527,6 +1827,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 10, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1534,6 +1835,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 13, 0, 1)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -1541,6 +1843,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 13, 20, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1548,6 +1851,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 15, 45, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1597,6 +1901,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 22, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1604,6 +1909,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 16, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1663,6 +1969,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 8, 55, 46)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1670,6 +1977,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 8, 56, 15)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1677,6 +1985,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 12, 25, 57)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 4,
@@ -1684,6 +1993,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 12, 26, 46)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 5,
@@ -1691,6 +2001,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 12, 28, 7)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 6,
@@ -1698,6 +2009,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 12, 58, 7)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 7,
@@ -1705,6 +2017,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 17, 0, 7)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 8,
@@ -1712,6 +2025,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 17, 15, 7)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1719,6 +2033,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 18, 27, 40)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1766,6 +2081,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 8, 22, 11)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1773,6 +2089,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 7, 12, 16, 32)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1822,6 +2139,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1829,6 +2147,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1836,6 +2155,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 30, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1843,6 +2163,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 17, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1897,6 +2218,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1904,6 +2226,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1911,6 +2234,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 16, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1918,6 +2242,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 21, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -1972,6 +2297,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -1979,6 +2305,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 12, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1986,6 +2313,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 16, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 3,
@@ -1993,6 +2321,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 23, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -2047,6 +2376,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 4, 8, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -2054,6 +2384,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 4, 14, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -2113,6 +2444,7 @@ mod tests {
                                 id: 1,
                                 status: Status::Connect,
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 0, 0).unwrap().to_utc(),
+                                ..Default::default()
                             },
                             Entry {
                                 id: 2,
@@ -2120,6 +2452,7 @@ mod tests {
                                 time: Local.with_ymd_and_hms(2022, 2, 2, 14, 0, 0)
                                     .unwrap()
                                     .to_utc(),
+                                ..Default::default()
                             },
                         ].to_vec(),
                         ..Default::default()
@@ -2162,4 +2495,204 @@ mod tests {
             );
         }
     }
+
+    mod daylight_saving {
+        use chrono::{ TimeZone, Utc };
+        use chrono_tz::Europe::Berlin;
+
+        use crate::Entries;
+
+        use super::*;
+
+        fn data(times: &[(Status, DateTime<Utc>)]) -> TimeData {
+            TimeData {
+                entries: Entries {
+                    data: times
+                        .iter()
+                        .enumerate()
+                        .map(|(n, (status, time))| Entry {
+                            id: (n + 1) as u8,
+                            status: status.to_owned(),
+                            time: time.to_owned(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        fn settings(sunday: u16) -> Settings {
+            Settings {
+                timezone: Berlin.into(),
+                workperday: WorkPerDayInMinutes {
+                    sunday,
+                    ..Default::default()
+                },
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn should_calculate_spring_forward() {
+            logger();
+            // clocks skip from 02:00 to 03:00
+            let data = data(
+                &[
+                    (Status::Connect, Berlin.with_ymd_and_hms(2022, 3, 27, 1, 0, 0).unwrap().to_utc()),
+                    (Status::End, Berlin.with_ymd_and_hms(2022, 3, 27, 10, 0, 0).unwrap().to_utc()),
+                ]
+            );
+            let status = StatusDaily::builder().data(data).settings(settings(0)).build().unwrap();
+
+            assert_eq!("01:00", status.start.as_ref().unwrap().to_string());
+            assert_eq!("10:00", status.end.as_ref().unwrap().to_string());
+            assert_eq!(Duration::hours(8), status.online.as_ref().unwrap().duration);
+            assert_eq!(Duration::hours(8), status.worktime.duration);
+        }
+
+        #[test]
+        fn should_calculate_fall_back() {
+            logger();
+            // clocks return from 03:00 to 02:00
+            let data = data(
+                &[
+                    (Status::Connect, Berlin.with_ymd_and_hms(2022, 10, 30, 1, 0, 0).unwrap().to_utc()),
+                    (Status::Break, Berlin.with_ymd_and_hms(2022, 10, 30, 6, 0, 0).unwrap().to_utc()),
+                    (Status::Connect, Berlin.with_ymd_and_hms(2022, 10, 30, 6, 30, 0).unwrap().to_utc()),
+                    (Status::End, Berlin.with_ymd_and_hms(2022, 10, 30, 10, 0, 0).unwrap().to_utc()),
+                ]
+            );
+            let status = StatusDaily::builder().data(data).settings(settings(0)).build().unwrap();
+
+            assert_eq!("01:00", status.start.as_ref().unwrap().to_string());
+            assert_eq!("10:00", status.end.as_ref().unwrap().to_string());
+            assert_eq!(Duration::hours(10), status.online.as_ref().unwrap().duration);
+            assert_eq!(Duration::minutes(9 * 60 + 30), status.worktime.duration);
+            assert!(status.to_string().contains("Break taken: 06:00 - 06:30"));
+        }
+
+        #[test]
+        fn should_calculate_night_shift() {
+            logger();
+            let data = data(
+                &[
+                    (Status::Connect, Berlin.with_ymd_and_hms(2022, 2, 2, 22, 0, 0).unwrap().to_utc()),
+                    (Status::Break, Berlin.with_ymd_and_hms(2022, 2, 3, 1, 0, 0).unwrap().to_utc()),
+                    (Status::Connect, Berlin.with_ymd_and_hms(2022, 2, 3, 1, 30, 0).unwrap().to_utc()),
+                    (Status::End, Berlin.with_ymd_and_hms(2022, 2, 3, 6, 30, 0).unwrap().to_utc()),
+                ]
+            );
+            let status = StatusDaily::builder().data(data).settings(settings(0)).build().unwrap();
+
+            assert_eq!(Duration::hours(8) + Duration::minutes(30), status.online.as_ref().unwrap().duration);
+            assert_eq!(Duration::hours(8), status.worktime.duration);
+            assert_eq!("22:00", status.start.as_ref().unwrap().clock());
+            assert_eq!("06:30 +1", status.end.as_ref().unwrap().clock());
+            let str = status.to_string();
+            assert!(str.contains("Break taken: 01:00 +1 - 01:30 +1"), "{}", str);
+        }
+
+        #[test]
+        fn should_output_times_with_offset() {
+            logger();
+            let data = data(
+                &[
+                    (Status::Connect, Berlin.with_ymd_and_hms(2022, 3, 27, 1, 0, 0).unwrap().to_utc()),
+                    (Status::Break, Berlin.with_ymd_and_hms(2022, 3, 27, 5, 0, 0).unwrap().to_utc()),
+                    (Status::Connect, Berlin.with_ymd_and_hms(2022, 3, 27, 5, 30, 0).unwrap().to_utc()),
+                ]
+            );
+            let output = StatusDaily::builder().data(data).settings(settings(8 * 60)).build().unwrap().output();
+
+            assert_eq!("2022-03-27T01:00:00+01:00", output.start.unwrap().to_rfc3339());
+            assert_eq!(None, output.end);
+            assert_eq!("2022-03-27T10:30:00+02:00", output.est_end.unwrap().to_rfc3339());
+            assert_eq!((30, 480), (output.r#break, output.expected));
+        }
+
+        #[test]
+        fn should_estimate_end_after_switch() {
+            logger();
+            let data = data(
+                &[(Status::Connect, Berlin.with_ymd_and_hms(2022, 3, 27, 1, 0, 0).unwrap().to_utc())]
+            );
+            let status = StatusDaily::builder().data(data).settings(settings(8 * 60)).build().unwrap();
+
+            // 8 hours of work end at 10:00 on the wall clock
+            assert_eq!("10:00", status.est_end.to_string());
+        }
+    }
+
+    mod rounding {
+        use chrono::{ TimeZone, Utc };
+
+        use crate::{ Entries, Rounding, RoundingMode, RoundingSettings };
+
+        use super::*;
+
+        #[test]
+        fn should_round_start_end_and_worktime() {
+            logger();
+            let time = |hour: u32, minute: u32| Utc.with_ymd_and_hms(2022, 2, 2, hour, minute, 0).unwrap();
+            let data = TimeData {
+                entries: Entries {
+                    data: [
+                        (Status::Connect, time(8, 7)),
+                        (Status::Break, time(12, 0)),
+                        (Status::Connect, time(12, 30)),
+                        (Status::End, time(16, 52)),
+                    ]
+                        .iter()
+                        .enumerate()
+                        .map(|(n, (status, time))| Entry {
+                            id: (n + 1) as u8,
+                            status: status.to_owned(),
+                            time: time.to_owned(),
+                            ..Default::default()
+                        })
+                        .collect(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+            let settings = Settings {
+                timezone: chrono_tz::UTC.into(),
+                rounding: RoundingSettings {
+                    start: Rounding { minutes: 15, mode: RoundingMode::Up },
+                    end: Rounding { minutes: 15, mode: RoundingMode::Down },
+                    worktime: Rounding { minutes: 30, mode: RoundingMode::Nearest },
+                },
+                ..Default::default()
+            };
+
+            let status = StatusDaily::builder().data(data).settings(settings).build().unwrap();
+
+            assert_eq!(Duration::minutes(8 * 60 + 15), status.worktime.duration);
+            assert_eq!("08:15", status.rounded_start.as_ref().unwrap().to_string());
+            assert_eq!("16:45", status.rounded_end.as_ref().unwrap().to_string());
+            // 08:15 to 16:45 with a break of 30 minutes
+            assert_eq!(Duration::hours(8), status.rounded_worktime.duration);
+            assert!(status.to_string().contains("Rounded:     08:00 (08:15 - 16:45)"));
+
+            let output = status.output();
+            assert_eq!("2022-02-02T08:15:00+00:00", output.rounded_start.unwrap().to_rfc3339());
+            assert_eq!((8 * 60 + 15, 8 * 60), (output.worktime, output.rounded_worktime));
+        }
+
+        #[test]
+        fn should_not_round_without_calculated_break() {
+            logger();
+            let mut status = StatusDaily {
+                settings: Some(Settings::default()),
+                start: Some(StatusTime::from(Duration::hours(8))),
+                end: Some(StatusTime::from(Duration::hours(16))),
+                ..Default::default()
+            };
+            status.set_rounded();
+            assert_eq!("08:00", status.rounded_start.as_ref().unwrap().to_string());
+            assert_eq!(Duration::zero(), status.rounded_worktime.duration);
+        }
+    }
 }
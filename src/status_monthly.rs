@@ -0,0 +1,393 @@
+use std::{ fmt::Display, io::Write };
+
+use chrono::{ DateTime, Datelike, NaiveDate, Utc };
+
+use crate::{
+    decimal_hours,
+    fmt_overtime,
+    Settings,
+    StatusDaily,
+    StatusRangeBuilder,
+    StatusTable,
+    StatusTime,
+    TimeDataMonthly,
+    TrackerError,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct StatusMonthly {
+    month: u32,
+    year: i32,
+    total: StatusTime,
+    expected: StatusTime,
+    overtime: StatusTime,
+    decimal: f64,
+    weeks: Vec<StatusMonthlyWeek>,
+    entries: Vec<(DateTime<Utc>, StatusDaily)>,
+}
+
+/// Subtotal of a week within the month.
+#[derive(Clone, Default, Debug)]
+pub struct StatusMonthlyWeek {
+    week: u32,
+    total: StatusTime,
+    expected: StatusTime,
+    overtime: StatusTime,
+    decimal: f64,
+}
+
+impl StatusMonthly {
+    pub fn builder() -> StatusMonthlyBuilder {
+        StatusMonthlyBuilder {
+            ..Default::default()
+        }
+    }
+
+    fn name(&self) -> String {
+        match NaiveDate::from_ymd_opt(self.year, self.month, 1) {
+            Some(d) => d.format("%B %Y").to_string(),
+            None => format!("{}-{}", self.year, self.month),
+        }
+    }
+
+    /// Table of the days of the month with subtotals per week, written to `out`.
+    pub fn format_table<W: Write>(&self, out: &mut W) -> Result<(), TrackerError> {
+        log::trace!("generating monthly status table");
+        let mut table = StatusTable::new(false);
+        self.weeks.iter().for_each(|w| {
+            self.entries
+                .iter()
+                .filter(|(date, _)| date.iso_week().week() == w.week)
+                .for_each(|(date, status)| {
+                    table.day(date.format("%a %d %b").to_string(), status);
+                });
+            table.total(format!("Total week {}", w.week), &w.total, &w.overtime, w.decimal);
+            table.separator();
+        });
+        table.total(format!("Total {}", self.name()), &self.total, &self.overtime, self.decimal);
+        table.print(out)
+    }
+}
+
+#[derive(Default)]
+pub struct StatusMonthlyBuilder {
+    settings: Option<Settings>,
+    data: Option<TimeDataMonthly>,
+}
+
+impl StatusMonthlyBuilder {
+    pub fn data(&mut self, data: TimeDataMonthly) -> &mut Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn settings(&mut self, settings: Settings) -> &mut Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    pub fn build(&self) -> Result<StatusMonthly, TrackerError> {
+        let settings = match self.settings.to_owned() {
+            Some(s) => s,
+            None => {
+                return Err(TrackerError::StatusMonthlyError {
+                    message: "settings not defined".to_owned(),
+                });
+            }
+        };
+
+        let data = match self.data.to_owned() {
+            Some(d) => d,
+            None => {
+                return Err(TrackerError::StatusMonthlyError {
+                    message: "data not defined".to_owned(),
+                });
+            }
+        };
+
+        let mut entries: Vec<(DateTime<Utc>, StatusDaily)> = Vec::new();
+        let mut weeks: Vec<StatusMonthlyWeek> = Vec::new();
+        // decimal hours are based on the rounded work time
+        let mut rounded: Vec<StatusTime> = Vec::new();
+
+        for d in data.entries.iter() {
+            log::trace!("processing: {:?}", d);
+            let date = d.date.unwrap();
+            let (status, expected) = StatusRangeBuilder::daily(d, &settings)?;
+
+            let week = date.iso_week().week();
+            if weeks.last().map(|w| w.week) != Some(week) {
+                weeks.push(StatusMonthlyWeek { week, ..Default::default() });
+                rounded.push(StatusTime::default());
+            }
+            if let Some(r) = rounded.last_mut() {
+                *r += status.rounded_worktime.to_owned();
+            }
+            let w = weeks.last_mut().unwrap();
+            w.total += status.worktime.to_owned();
+            w.expected += expected;
+            w.overtime += status.overtime.to_owned();
+
+            entries.push((date, status));
+        }
+
+        weeks
+            .iter_mut()
+            .zip(rounded.iter())
+            .for_each(|(w, r)| {
+                w.decimal = decimal_hours(r);
+            });
+
+        let mut total = StatusTime::default();
+        let mut expected = StatusTime::default();
+        let mut overtime = StatusTime::default();
+        weeks.iter().for_each(|w| {
+            total += w.total.to_owned();
+            expected += w.expected.to_owned();
+            overtime += w.overtime.to_owned();
+        });
+
+        log::info!("totally {} working time and {} overtime", total, overtime);
+
+        let decimal = decimal_hours(&rounded.into_iter().fold(StatusTime::default(), |t, r| t + r));
+        Ok(StatusMonthly {
+            month: data.month,
+            year: data.year,
+            total,
+            expected,
+            overtime,
+            decimal,
+            weeks,
+            entries,
+        })
+    }
+}
+
+impl Display for StatusMonthly {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = 10;
+
+        let mut lines = vec![
+            format!(
+                " {:width$} | {:width$} | {:width$} | {:width$} | {:width$}",
+                "Week",
+                "Work time",
+                "Expected",
+                "Overtime",
+                "Decimal"
+            ),
+            format!(" {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$}", "")
+        ];
+
+        self.weeks.iter().for_each(|w| {
+            lines.push(
+                format!(
+                    " {0:width$} | {1: >width$} | {2: >width$} | {3: >width$} | {4: >width$}",
+                    w.week,
+                    format!("{}", w.total),
+                    format!("{}", w.expected),
+                    fmt_overtime(&w.overtime),
+                    format!("{:.2}", w.decimal)
+                )
+            );
+        });
+
+        lines.push(
+            format!(" {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$}", "")
+        );
+        lines.push(
+            format!(
+                " {0:width$} | {1: >width$} | {2: >width$} | {3: >width$} | {4: >width$}",
+                format!("{:04}-{:02}", self.year, self.month),
+                format!("{}", self.total),
+                format!("{}", self.expected),
+                fmt_overtime(&self.overtime),
+                format!("{:.2}", self.decimal)
+            )
+        );
+        writeln!(f, "{}", lines.join("\n"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::ops::Add;
+
+    use chrono::{ Duration, TimeZone };
+
+    use crate::{ BreakLimit, Entries, Entry, Status, TimeData };
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "trace");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn get_settings() -> Settings {
+        Settings {
+            limits: [
+                BreakLimit {
+                    start: 8 * 60,
+                    minutes: 45,
+                },
+            ].to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn get_entries(day: u32, end: u32, end_minutes: u32) -> Entries {
+        Entries {
+            data: [
+                Entry {
+                    id: 1,
+                    status: Status::Connect,
+                    time: Utc.with_ymd_and_hms(2022, 3, day, 0, 0, 0).unwrap(),
+                    ..Default::default()
+                },
+                Entry {
+                    id: 2,
+                    status: Status::Break,
+                    time: Utc.with_ymd_and_hms(2022, 3, day, 4, 0, 0).unwrap(),
+                    ..Default::default()
+                },
+                Entry {
+                    id: 3,
+                    status: Status::Connect,
+                    time: Utc.with_ymd_and_hms(2022, 3, day, 4, 30, 0).unwrap(),
+                    ..Default::default()
+                },
+                Entry {
+                    id: 4,
+                    status: Status::End,
+                    time: Utc.with_ymd_and_hms(2022, 3, day, end, end_minutes, 0).unwrap(),
+                    ..Default::default()
+                },
+            ].to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// March 2022, every working day 8 hours and one day 10 hours.
+    fn get_time_data() -> TimeDataMonthly {
+        let entries = (1..=31)
+            .map(|day| {
+                let date = Utc.with_ymd_and_hms(2022, 3, day, 0, 0, 0).unwrap();
+                let entries = match date.weekday() {
+                    chrono::Weekday::Sat | chrono::Weekday::Sun => Entries::default(),
+                    _ if day == 9 => get_entries(day, 10, 45),
+                    _ => get_entries(day, 8, 45),
+                };
+                TimeData {
+                    date: Some(date),
+                    entries,
+                    ..Default::default()
+                }
+            })
+            .collect::<Vec<TimeData>>();
+        TimeDataMonthly {
+            entries,
+            month: 3,
+            year: 2022,
+        }
+    }
+
+    mod builder {
+        use super::*;
+
+        #[test]
+        fn should_calculate_month() -> Result<(), TrackerError> {
+            logger();
+            let mut b = StatusMonthly::builder();
+            let s = b.data(get_time_data()).settings(get_settings()).build()?;
+
+            log::debug!("{}", s);
+
+            // 23 working days in march 2022
+            assert_eq!(StatusTime::from(Duration::hours(23 * 8)), s.expected);
+            assert_eq!(StatusTime::from(Duration::hours(23 * 8 + 2)), s.total);
+            assert_eq!(StatusTime::from(Duration::hours(2)), s.overtime);
+            assert_eq!(186.0, s.decimal);
+            Ok(())
+        }
+
+        #[test]
+        fn should_calculate_weeks() -> Result<(), TrackerError> {
+            logger();
+            let mut b = StatusMonthly::builder();
+            let s = b.data(get_time_data()).settings(get_settings()).build()?;
+
+            assert_eq!([9, 10, 11, 12, 13].to_vec(), s.weeks.iter().map(|w| w.week).collect::<Vec<u32>>());
+            // week 9 starts on tuesday the 1st
+            assert_eq!(StatusTime::from(Duration::hours(4 * 8)), s.weeks[0].total);
+            assert_eq!(StatusTime::from(Duration::hours(5 * 8 + 2)), s.weeks[1].total);
+            assert_eq!(StatusTime::from(Duration::hours(2)), s.weeks[1].overtime);
+            assert_eq!(42.0, s.weeks[1].decimal);
+            Ok(())
+        }
+
+        #[test]
+        fn should_count_missing_day() -> Result<(), TrackerError> {
+            logger();
+            let mut data = get_time_data();
+            data.entries[2].entries = Entries::default();
+
+            let mut b = StatusMonthly::builder();
+            let s = b.data(data).settings(get_settings()).build()?;
+
+            assert_eq!(StatusTime::from(Duration::hours(-6)), s.overtime);
+            Ok(())
+        }
+
+        #[test]
+        fn should_format_table() -> Result<(), TrackerError> {
+            logger();
+            let mut b = StatusMonthly::builder();
+            let s = b.data(get_time_data()).settings(get_settings()).build()?;
+            let mut out = Vec::new();
+            s.format_table(&mut out)?;
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.contains(&format!("{:.2}", s.decimal)), "{}", out);
+            assert!(out.contains("Total "), "{}", out);
+            Ok(())
+        }
+    }
+
+    mod format {
+        use colored::control::ShouldColorize;
+
+        use super::*;
+
+        #[test]
+        fn status_monthly() {
+            logger();
+            let s = StatusMonthly {
+                month: 3,
+                year: 2022,
+                total: StatusTime::from(Duration::hours(41).add(Duration::minutes(30))),
+                expected: StatusTime::from(Duration::hours(40)),
+                overtime: StatusTime::from(Duration::minutes(90)),
+                decimal: 41.5,
+                weeks: [
+                    StatusMonthlyWeek {
+                        week: 9,
+                        total: StatusTime::from(Duration::hours(41).add(Duration::minutes(30))),
+                        expected: StatusTime::from(Duration::hours(40)),
+                        overtime: StatusTime::from(Duration::minutes(90)),
+                        decimal: 41.5,
+                    },
+                ].to_vec(),
+                entries: Vec::new(),
+            };
+
+            if !ShouldColorize::from_env().should_colorize() {
+                assert_eq!(
+                    " Week       | Work time  | Expected   | Overtime   | Decimal   \n ---------- | ---------- | ---------- | ---------- | ----------\n          9 |      41:30 |      40:00 |     +01:30 |      41.50\n ---------- | ---------- | ---------- | ---------- | ----------\n 2022-03    |      41:30 |      40:00 |     +01:30 |      41.50\n",
+                    format!("{}", s)
+                );
+            } else {
+                assert!(format!("{}", s).contains(" 2022-03    |      41:30 |      40:00 |"));
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{ DateTime, FixedOffset, NaiveDate };
+use serde::Serialize;
+
+use crate::{ AbsenceType, StatusTime, TrackerError };
+
+/// Output of the status command, text for humans or a serialized schema for scripts.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Serialized `value`, `None` for text output which is displayed instead.
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<Option<String>, TrackerError> {
+        let error = |message: String| TrackerError::StatusError { message };
+        match self {
+            OutputFormat::Text => Ok(None),
+            OutputFormat::Json =>
+                serde_json::to_string_pretty(value)
+                    .map(Some)
+                    .map_err(|e| error(e.to_string())),
+            OutputFormat::Yaml =>
+                serde_yaml_ng::to_string(value)
+                    .map(|y| Some(y.trim_end().to_owned()))
+                    .map_err(|e| error(e.to_string())),
+        }
+    }
+}
+
+/// Status of a day. Times are RFC 3339 at the configured timezone, durations are minutes.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct StatusDailyOutput {
+    pub date: Option<NaiveDate>,
+    pub absence: Option<AbsenceType>,
+    pub start: Option<DateTime<FixedOffset>>,
+    /// `None` while the day is not ended.
+    pub end: Option<DateTime<FixedOffset>>,
+    /// End when the expected work time and break are reached.
+    pub est_end: Option<DateTime<FixedOffset>>,
+    /// Start and end after the configured rounding.
+    pub rounded_start: Option<DateTime<FixedOffset>>,
+    pub rounded_end: Option<DateTime<FixedOffset>>,
+    pub r#break: i64,
+    pub exp_break: i64,
+    pub online: i64,
+    pub worktime: i64,
+    pub rounded_worktime: i64,
+    pub expected: i64,
+    pub overtime: i64,
+    pub projects: Vec<ProjectOutput>,
+    pub note: Option<String>,
+    pub notes: Vec<NoteOutput>,
+}
+
+/// Status of a week with every day of it.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct StatusWeeklyOutput {
+    pub week: i8,
+    pub worktime: i64,
+    pub rounded_worktime: i64,
+    pub overtime: i64,
+    /// Rounded work time in hours.
+    pub decimal: f64,
+    pub days: Vec<StatusDailyOutput>,
+    pub projects: Vec<ProjectOutput>,
+}
+
+/// Work time of a project, `None` for segments without project.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct ProjectOutput {
+    pub project: Option<String>,
+    pub worktime: i64,
+}
+
+/// Note of the segment which started at `time`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct NoteOutput {
+    pub time: DateTime<FixedOffset>,
+    pub note: String,
+}
+
+impl From<(&Option<String>, &StatusTime)> for ProjectOutput {
+    fn from((project, time): (&Option<String>, &StatusTime)) -> Self {
+        ProjectOutput { project: project.to_owned(), worktime: time.duration.num_minutes() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_serialize() -> Result<(), TrackerError> {
+        let output = StatusWeeklyOutput {
+            week: 5,
+            worktime: 480,
+            days: vec![StatusDailyOutput { r#break: 30, ..Default::default() }],
+            ..Default::default()
+        };
+        assert_eq!(None, OutputFormat::Text.serialize(&output)?);
+
+        let json = OutputFormat::Json.serialize(&output)?.unwrap();
+        assert!(json.contains("\"worktime\": 480"));
+        assert!(json.contains("\"break\": 30"));
+
+        let yaml = OutputFormat::Yaml.serialize(&output)?.unwrap();
+        assert!(yaml.starts_with("week: 5\nworktime: 480\n"));
+        assert!(yaml.contains("  break: 30\n"));
+        Ok(())
+    }
+}
@@ -0,0 +1,343 @@
+use std::{ fmt::Display, io::Write, ops::Mul };
+
+use chrono::{ DateTime, Duration, NaiveDate, Utc };
+
+use crate::{
+    decimal_hours,
+    fmt_overtime,
+    Settings,
+    StatusDaily,
+    StatusTable,
+    StatusTime,
+    TimeData,
+    TimeDataRange,
+    TrackerError,
+};
+
+#[derive(Clone, Default, Debug)]
+pub struct StatusRange {
+    from: NaiveDate,
+    to: NaiveDate,
+    total: StatusTime,
+    expected: StatusTime,
+    overtime: StatusTime,
+    decimal: f64,
+    entries: Vec<(DateTime<Utc>, StatusDaily)>,
+}
+
+impl StatusRange {
+    pub fn builder() -> StatusRangeBuilder {
+        StatusRangeBuilder {
+            ..Default::default()
+        }
+    }
+
+    /// Sum of reports over the same days, eg. of several profiles.
+    pub fn sum(ranges: &[StatusRange]) -> StatusRange {
+        ranges.iter().fold(StatusRange::default(), |mut sum, r| {
+            sum.from = r.from;
+            sum.to = r.to;
+            sum.total += r.total.to_owned();
+            sum.expected += r.expected.to_owned();
+            sum.overtime += r.overtime.to_owned();
+            sum.decimal += r.decimal;
+            sum
+        })
+    }
+
+    /// Table of the days of the range, written to `out`.
+    pub fn format_table<W: Write>(&self, out: &mut W) -> Result<(), TrackerError> {
+        log::trace!("generating range status table");
+        let mut table = StatusTable::new(false);
+        self.entries.iter().for_each(|(date, status)| {
+            table.day(date.format("%a %d %b %Y").to_string(), status);
+        });
+        table.separator();
+        table.total(format!("Total {} - {}", self.from, self.to), &self.total, &self.overtime, self.decimal);
+        table.print(out)
+    }
+}
+
+#[derive(Default)]
+pub struct StatusRangeBuilder {
+    settings: Option<Settings>,
+    data: Option<TimeDataRange>,
+}
+
+impl StatusRangeBuilder {
+    pub fn data(&mut self, data: TimeDataRange) -> &mut Self {
+        self.data = Some(data);
+        self
+    }
+
+    pub fn settings(&mut self, settings: Settings) -> &mut Self {
+        self.settings = Some(settings);
+        self
+    }
+
+    pub fn build(&self) -> Result<StatusRange, TrackerError> {
+        let settings = match self.settings.to_owned() {
+            Some(s) => s,
+            None => {
+                return Err(TrackerError::StatusRangeError {
+                    message: "settings not defined".to_owned(),
+                });
+            }
+        };
+
+        let data = match self.data.to_owned() {
+            Some(d) => d,
+            None => {
+                return Err(TrackerError::StatusRangeError {
+                    message: "data not defined".to_owned(),
+                });
+            }
+        };
+
+        let mut entries: Vec<(DateTime<Utc>, StatusDaily)> = Vec::new();
+
+        let mut total = StatusTime::default();
+        let mut expected = StatusTime::default();
+        let mut overtime = StatusTime::default();
+        let mut rounded = StatusTime::default();
+
+        for d in data.entries.iter() {
+            log::trace!("processing: {:?}", d);
+            let (s, e) = StatusRangeBuilder::daily(d, &settings)?;
+            total += s.worktime.to_owned();
+            rounded += s.rounded_worktime.to_owned();
+            expected += e;
+            overtime += s.overtime.to_owned();
+            entries.push((d.date.unwrap(), s));
+        }
+
+        log::info!("totally {} working time and {} overtime", total, overtime);
+
+        // decimal hours are based on the rounded work time
+        let decimal = decimal_hours(&rounded);
+        Ok(StatusRange {
+            from: data.from,
+            to: data.to,
+            total,
+            expected,
+            overtime,
+            decimal,
+            entries,
+        })
+    }
+
+    /// Status and expected work time of a single day.
+    ///
+    /// Days without entries count as missing expected work time.
+    pub(crate) fn daily(
+        data: &TimeData,
+        settings: &Settings
+    ) -> Result<(StatusDaily, StatusTime), TrackerError> {
+        let expected = StatusTime::from(Duration::minutes(data.expected(settings).into()));
+        if data.entries.data.is_empty() {
+            let s = StatusDaily::builder().empty_with_overtime(expected.to_owned().mul(-1));
+            Ok((s, expected))
+        } else {
+            let s = StatusDaily::builder().data(data.to_owned()).settings(settings.clone()).build()?;
+            log::info!("got {} working time and {} overtime", s.worktime, s.overtime);
+            Ok((s, expected))
+        }
+    }
+}
+
+impl Display for StatusRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let width = 10;
+
+        let line1 = format!(
+            " {:width$} | {:width$} | {:width$} | {:width$} | {:width$} | {:width$}",
+            "From",
+            "To",
+            "Work time",
+            "Expected",
+            "Overtime",
+            "Decimal"
+        );
+        let line2 = format!(
+            " {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$}",
+            ""
+        );
+
+        let ot_fmt = fmt_overtime(&self.overtime);
+
+        let line3 = format!(
+            " {0:width$} | {1:width$} | {2: >width$} | {3: >width$} | {4: >width$} | {5: >width$}",
+            format!("{}", self.from),
+            format!("{}", self.to),
+            format!("{}", self.total),
+            format!("{}", self.expected),
+            ot_fmt,
+            format!("{:.2}", self.decimal)
+        );
+        write!(f, "{}\n{}\n{}\n", line1, line2, line3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::TimeZone;
+
+    use crate::{ AbsenceType, BreakLimit, Entries, Entry, Status };
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "trace");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    fn get_settings() -> Settings {
+        Settings {
+            limits: [
+                BreakLimit {
+                    start: 6 * 60,
+                    minutes: 30,
+                },
+            ].to_vec(),
+            ..Default::default()
+        }
+    }
+
+    fn get_time_data(day: u32, hours: u32) -> TimeData {
+        TimeData {
+            date: Some(Utc.with_ymd_and_hms(2022, 3, day, 0, 0, 0).unwrap()),
+            entries: Entries {
+                data: [
+                    Entry {
+                        id: 1,
+                        status: Status::Connect,
+                        time: Utc.with_ymd_and_hms(2022, 3, day, 6, 0, 0).unwrap(),
+                        ..Default::default()
+                    },
+                    Entry {
+                        id: 2,
+                        status: Status::End,
+                        time: Utc.with_ymd_and_hms(2022, 3, day, 6 + hours, 30, 0).unwrap(),
+                        ..Default::default()
+                    },
+                ].to_vec(),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Friday to Monday with one missing monday.
+    fn get_range() -> TimeDataRange {
+        TimeDataRange {
+            entries: [
+                get_time_data(11, 9),
+                TimeData {
+                    date: Some(Utc.with_ymd_and_hms(2022, 3, 12, 0, 0, 0).unwrap()),
+                    ..Default::default()
+                },
+                TimeData {
+                    date: Some(Utc.with_ymd_and_hms(2022, 3, 13, 0, 0, 0).unwrap()),
+                    ..Default::default()
+                },
+                TimeData {
+                    date: Some(Utc.with_ymd_and_hms(2022, 3, 14, 0, 0, 0).unwrap()),
+                    ..Default::default()
+                },
+                get_time_data(15, 8),
+            ].to_vec(),
+            from: NaiveDate::from_ymd_opt(2022, 3, 11).unwrap(),
+            to: NaiveDate::from_ymd_opt(2022, 3, 15).unwrap(),
+        }
+    }
+
+    mod builder {
+        use super::*;
+
+        #[test]
+        fn should_calculate_range() -> Result<(), TrackerError> {
+            logger();
+            let mut b = StatusRange::builder();
+            let s = b.data(get_range()).settings(get_settings()).build()?;
+
+            log::debug!("{}", s);
+
+            assert_eq!(StatusTime::from(Duration::hours(17)), s.total);
+            assert_eq!(StatusTime::from(Duration::hours(24)), s.expected);
+            assert_eq!(StatusTime::from(Duration::hours(-7)), s.overtime);
+            assert_eq!(17.0, s.decimal);
+            assert_eq!(5, s.entries.len());
+            Ok(())
+        }
+
+        #[test]
+        fn should_credit_absence() -> Result<(), TrackerError> {
+            logger();
+            let mut range = get_range();
+            range.entries[3].absence = Some(AbsenceType::Sick);
+            let s = StatusRange::builder().data(range).settings(get_settings()).build()?;
+
+            assert_eq!(StatusTime::from(Duration::hours(16)), s.expected);
+            assert_eq!(StatusTime::from(Duration::hours(1)), s.overtime);
+            Ok(())
+        }
+
+        #[test]
+        fn should_sum_ranges() -> Result<(), TrackerError> {
+            logger();
+            let s = StatusRange::builder().data(get_range()).settings(get_settings()).build()?;
+            let sum = StatusRange::sum(&[s.to_owned(), s.to_owned()]);
+
+            assert_eq!(s.from, sum.from);
+            assert_eq!(StatusTime::from(Duration::hours(34)), sum.total);
+            assert_eq!(StatusTime::from(Duration::hours(48)), sum.expected);
+            assert_eq!(StatusTime::from(Duration::hours(-14)), sum.overtime);
+            assert_eq!(34.0, sum.decimal);
+            Ok(())
+        }
+
+        #[test]
+        fn no_settings() {
+            let mut b = StatusRange::builder();
+            let res = b.data(get_range()).build();
+            assert!(res.is_err());
+            assert_eq!("range status error: settings not defined", res.err().unwrap().to_string());
+        }
+
+        #[test]
+        fn should_format_table() -> Result<(), TrackerError> {
+            logger();
+            let mut b = StatusRange::builder();
+            let s = b.data(get_range()).settings(get_settings()).build()?;
+            let mut out = Vec::new();
+            s.format_table(&mut out)?;
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.contains(&format!("{:.2}", s.decimal)), "{}", out);
+            assert!(out.contains("Total "), "{}", out);
+            Ok(())
+        }
+    }
+
+    mod format {
+        use colored::control::ShouldColorize;
+
+        use super::*;
+
+        #[test]
+        fn status_range() -> Result<(), TrackerError> {
+            logger();
+            let mut b = StatusRange::builder();
+            let s = b.data(get_range()).settings(get_settings()).build()?;
+
+            if ShouldColorize::from_env().should_colorize() {
+                assert!(format!("{}", s).contains(" 2022-03-11 | 2022-03-15 |      17:00 |      24:00 |"));
+            } else {
+                assert_eq!(
+                    " From       | To         | Work time  | Expected   | Overtime   | Decimal   \n ---------- | ---------- | ---------- | ---------- | ---------- | ----------\n 2022-03-11 | 2022-03-15 |      17:00 |      24:00 |     -07:00 |      17.00\n",
+                    format!("{}", s)
+                );
+            }
+            Ok(())
+        }
+    }
+}
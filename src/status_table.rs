@@ -0,0 +1,129 @@
+use std::{ io::Write, ops::Mul };
+
+use chrono::Duration;
+use colored::{ ColoredString, Colorize };
+use prettytable::{ format, Cell, Row, Table };
+
+use crate::{ StatusDaily, StatusTime, TrackerError };
+
+/// Decimal hours of `time`, eg. 7.5 for 07:30.
+pub(crate) fn decimal_hours(time: &StatusTime) -> f64 {
+    (time.hours as f64) + (time.minutes as f64) / 60.0
+}
+
+/// Overtime with its sign, missing time in red and additional time in yellow.
+pub(crate) fn fmt_overtime(overtime: &StatusTime) -> ColoredString {
+    match overtime.partial_cmp(&Duration::minutes(0).into()).unwrap() {
+        std::cmp::Ordering::Less => format!("-{}", overtime.to_owned().mul(-1)).bright_red(),
+        std::cmp::Ordering::Equal => format!("{}", overtime).normal(),
+        std::cmp::Ordering::Greater => format!("+{}", overtime).bright_yellow(),
+    }
+}
+
+/// Table of daily statuses with totals, shared by the weekly, monthly and range reports.
+pub(crate) struct StatusTable {
+    table: Table,
+    notes: bool,
+}
+
+impl StatusTable {
+    /// Table with the notes of the days as last column if `notes` is set.
+    pub(crate) fn new(notes: bool) -> Self {
+        let mut table = Table::new();
+        table.set_format(*format::consts::FORMAT_NO_COLSEP);
+
+        let width = 7;
+        let mut titles = ["Date", "Start", "End", "Break", "Worktime", "Overtime", "Decimal"]
+            .iter()
+            .map(|t| format!("{:width$}", t))
+            .collect::<Vec<String>>();
+        if notes {
+            titles.push("Notes".to_owned());
+        }
+        table.set_titles(StatusTable::cells(titles));
+        StatusTable { table, notes }
+    }
+
+    pub(crate) fn day(&mut self, date: String, status: &StatusDaily) {
+        let mut cells = vec![
+            date,
+            status.start.to_owned().unwrap_or_default().clock(),
+            status.end.to_owned().unwrap_or_default().clock(),
+            format!("{}", status.r#break.to_owned().unwrap_or_default()),
+            format!("{}", status.worktime),
+            format!("{}", status.overtime),
+            "".to_owned()
+        ];
+        if self.notes {
+            cells.push(status.notes_line());
+        }
+        self.table.add_row(StatusTable::cells(cells));
+    }
+
+    /// Summary row, the decimal hours are shown in their own column.
+    pub(crate) fn total(&mut self, name: String, total: &StatusTime, overtime: &StatusTime, decimal: f64) {
+        let cells = vec![
+            name,
+            "".to_owned(),
+            "".to_owned(),
+            "".to_owned(),
+            format!("{}", total),
+            format!("{}", overtime),
+            format!("{:.2}", decimal)
+        ];
+        self.table.add_row(StatusTable::cells(cells));
+    }
+
+    /// Work time of a project below the work time of the days.
+    pub(crate) fn project(&mut self, name: &str, total: &StatusTime) {
+        let cells = vec![
+            format!("Project {}", name),
+            "".to_owned(),
+            "".to_owned(),
+            "".to_owned(),
+            format!("{}", total)
+        ];
+        self.table.add_row(StatusTable::cells(cells));
+    }
+
+    pub(crate) fn separator(&mut self) {
+        self.table.add_empty_row();
+    }
+
+    pub(crate) fn print<W: Write>(&self, out: &mut W) -> Result<(), TrackerError> {
+        log::trace!("print table");
+        self.table.print(out)?;
+        Ok(())
+    }
+
+    fn cells(cells: Vec<String>) -> Row {
+        Row::new(cells.iter().map(|c| Cell::new(c)).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_convert_to_decimal_hours() {
+        assert_eq!(7.5, decimal_hours(&StatusTime::from(Duration::minutes(450))));
+        assert_eq!(-0.25, decimal_hours(&StatusTime::from(Duration::minutes(-15))));
+    }
+
+    #[test]
+    fn should_align_total_with_titles() -> Result<(), TrackerError> {
+        let mut table = StatusTable::new(false);
+        table.total("Total week 10".to_owned(), &Duration::hours(40).into(), &Duration::minutes(30).into(), 40.0);
+        let mut out = Vec::new();
+        table.print(&mut out)?;
+
+        let out = String::from_utf8(out).unwrap();
+        let lines = out.lines().collect::<Vec<&str>>();
+        let title = lines.iter().find(|l| l.contains("Decimal")).unwrap();
+        let total = lines.iter().find(|l| l.contains("Total week 10")).unwrap();
+        assert_eq!(title.find("Decimal"), total.find("40.00"));
+        assert_eq!(title.find("Worktime"), total.find("40:00"));
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use std::ops::{ Add, AddAssign, Mul, Sub, SubAssign };
 
 use chrono::{ DateTime, Duration, Local, Timelike, Utc };
 
-use crate::Entry;
+use crate::{ Entry, WorkTimeZone };
 
 #[derive(Debug, Clone)]
 #[allow(unused)]
@@ -14,8 +14,26 @@ pub struct StatusTime {
 }
 
 impl StatusTime {
-    pub fn now() -> StatusTime {
-        StatusTime::from(Local::now())
+    /// Wall clock time of day of `time` at the work location.
+    pub fn at(time: DateTime<Utc>, zone: &WorkTimeZone) -> StatusTime {
+        let duration = Duration::seconds(zone.local(time).num_seconds_from_midnight().into());
+        StatusTime::from(duration)
+    }
+
+    /// Time of day, times of the following day are marked with `+1`.
+    pub fn clock(&self) -> String {
+        match self.hours {
+            h if h >= 24 => format!("{:0>2}:{:0>2} +{}", h % 24, self.minutes, h / 24),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Wall clock time of day of the entry at the work location.
+    pub fn entry(e: &Entry, zone: &WorkTimeZone) -> StatusTime {
+        StatusTime {
+            inner: Some(e.clone()),
+            ..StatusTime::at(e.time, zone)
+        }
     }
 }
 
@@ -47,14 +65,7 @@ impl std::fmt::Display for StatusTime {
 
 impl From<&Entry> for StatusTime {
     fn from(e: &Entry) -> Self {
-        let time = DateTime::<Local>::from(e.time);
-        let d = Duration::seconds(time.num_seconds_from_midnight().into());
-        StatusTime {
-            duration: d,
-            minutes: d.num_minutes() % 60,
-            hours: d.num_hours(),
-            inner: Some(e.clone()),
-        }
+        StatusTime::entry(e, &WorkTimeZone::default())
     }
 }
 
@@ -180,10 +191,17 @@ mod tests {
                 id: 1,
                 status: Status::Connect,
                 time: Local.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap().to_utc(),
+                ..Default::default()
             };
             let status = StatusTime::from(&data);
             assert_eq!("08:03", format!("{}", status));
         }
+
+        #[test]
+        fn should_format_clock_of_next_day() {
+            assert_eq!("22:15", StatusTime::from(Duration::minutes(22 * 60 + 15)).clock());
+            assert_eq!("06:05 +1", StatusTime::from(Duration::minutes(30 * 60 + 5)).clock());
+        }
     }
 
     mod from {
@@ -203,6 +221,7 @@ mod tests {
                 id: 2,
                 status: Status::Disconnect,
                 time: Utc.with_ymd_and_hms(2022, 2, 2, 8, 3, 0).unwrap(),
+                ..Default::default()
             };
             let status = StatusTime::from(&entry);
             assert!(
@@ -222,6 +241,19 @@ mod tests {
             assert_eq!(2, status.inner.unwrap().id);
         }
 
+        #[test]
+        fn should_create_at_work_location() {
+            let zone = WorkTimeZone::from(chrono_tz::America::New_York);
+            let entry = Entry {
+                time: Utc.with_ymd_and_hms(2022, 2, 2, 13, 3, 0).unwrap(),
+                ..Default::default()
+            };
+            assert_eq!("08:03", StatusTime::entry(&entry, &zone).to_string());
+            // daylight saving time
+            let time = Utc.with_ymd_and_hms(2022, 7, 2, 13, 3, 0).unwrap();
+            assert_eq!("09:03", StatusTime::at(time, &zone).to_string());
+        }
+
         #[test]
         fn should_create_from_datetime() {
             let l = DateTime::<Utc>::default();
@@ -1,10 +1,21 @@
-use std::{ env, fmt::Display, ops::Mul };
+use std::{ collections::BTreeMap, fmt::Display, io::Write };
 
 use chrono::{ DateTime, Duration, Utc };
-use colored::Colorize;
-use prettytable::{ format, Table };
 
-use crate::{ Settings, StatusDaily, StatusTime, TimeData, TimeDataWeekly, TrackerError };
+use crate::{
+    decimal_hours,
+    fmt_overtime,
+    ProjectOutput,
+    Settings,
+    StatusDaily,
+    StatusDailyOutput,
+    StatusTable,
+    StatusTime,
+    StatusWeeklyOutput,
+    TimeData,
+    TimeDataWeekly,
+    TrackerError,
+};
 
 #[derive(Clone, Default, Debug)]
 pub struct StatusWeekly {
@@ -22,68 +33,58 @@ impl StatusWeekly {
         }
     }
 
-    pub fn format_table(&self) {
-        let mut table = Table::new();
-        table.set_format(*format::consts::FORMAT_NO_COLSEP);
-
-        log::trace!("generating status table");
-
-        let width = 7;
-
-        log::trace!("set table titels");
+    /// Tracked time per project of the whole week.
+    pub fn projects(&self) -> BTreeMap<Option<String>, StatusTime> {
+        let mut projects: BTreeMap<Option<String>, StatusTime> = BTreeMap::new();
+        self.entries.iter().for_each(|(_, status)| {
+            status.projects.iter().for_each(|(p, t)| {
+                *projects.entry(p.to_owned()).or_default() += t.to_owned();
+            });
+        });
+        projects
+    }
 
-        table.set_titles(
-            row![
-                format!("{:width$}", "Date"),
-                format!("{:width$}", "Start"),
-                format!("{:width$}", "End"),
-                format!("{:width$}", "Break"),
-                format!("{:width$}", "Worktime"),
-                format!("{:width$}", "Overtime")
-            ]
-        );
+    /// Rounded work time of the whole week.
+    pub fn rounded(&self) -> StatusTime {
+        self.entries
+            .iter()
+            .fold(StatusTime::default(), |t, (_, s)| t + s.rounded_worktime.to_owned())
+    }
 
-        fn status_unwrap(status: Option<StatusTime>) -> StatusTime {
-            match status {
-                Some(val) => val,
-                None => StatusTime::default(),
-            }
+    /// Status in the schema of the JSON and YAML output.
+    pub fn output(&self) -> StatusWeeklyOutput {
+        StatusWeeklyOutput {
+            week: self.week,
+            worktime: self.total.duration.num_minutes(),
+            rounded_worktime: self.rounded().duration.num_minutes(),
+            overtime: self.overtime.duration.num_minutes(),
+            decimal: self.decimal,
+            days: self.entries
+                .iter()
+                .map(|(d, s)| StatusDailyOutput { date: Some(d.date_naive()), ..s.output() })
+                .collect(),
+            projects: self.projects().iter().map(ProjectOutput::from).collect(),
         }
+    }
 
-        log::trace!("iterate over entries");
-
+    /// Table of the days of the week, written to `out`.
+    pub fn format_table<W: Write>(&self, out: &mut W) -> Result<(), TrackerError> {
+        log::trace!("generating status table");
+        let mut table = StatusTable::new(true);
         self.entries.iter().for_each(|(date, status)| {
-            table.add_row(
-                row![
-                    date.to_owned().format("%a %d %b"),
-                    format!("{}", status_unwrap(status.start.to_owned())),
-                    format!("{}", status_unwrap(status.end.to_owned())),
-                    format!("{}", status_unwrap(status.r#break.to_owned())),
-                    format!("{}", status.worktime),
-                    format!("{}", status.overtime)
-                ]
-            );
+            table.day(date.format("%a %d %b").to_string(), status);
         });
-
-        table.add_empty_row();
-
-        log::trace!("add summary row");
-
-        table.add_row(
-            row![
-                format!("Total week {}", self.week),
-                "",
-                "",
-                self.decimal,
-                self.total,
-                self.overtime
-            ]
-        );
-
-        if env::var("RUST_TEST").is_err() {
-            log::trace!("print table to std");
-            table.printstd();
+        table.separator();
+        table.total(format!("Total week {}", self.week), &self.total, &self.overtime, self.decimal);
+
+        let projects = self.projects();
+        if projects.keys().any(|p| p.is_some()) {
+            table.separator();
+            projects.iter().for_each(|(p, t)| {
+                table.project(p.as_deref().unwrap_or("-"), t);
+            });
         }
+        table.print(out)
     }
 }
 
@@ -126,6 +127,7 @@ impl StatusWeeklyBuilder {
         let mut entries: Vec<(DateTime<Utc>, StatusDaily)> = Vec::new();
 
         let mut total = StatusTime::default();
+        let mut rounded = StatusTime::default();
         let mut overtime = StatusTime::default();
 
         data.entries.iter().for_each(|d: &TimeData| {
@@ -136,18 +138,16 @@ impl StatusWeeklyBuilder {
                 log::info!("got {} working time and {} overtime", s.worktime, s.overtime);
                 entries.append(&mut [(d.date.unwrap().to_owned(), s.to_owned())].to_vec());
                 total += s.worktime;
+                rounded += s.rounded_worktime;
                 overtime += s.overtime;
             } else {
-                let expected = self.settings
-                    .as_ref()
-                    .unwrap()
-                    .workperday.from_date(d.date.unwrap());
+                let expected = &d.expected(&settings);
                 if expected >= &0 {
                     let exh = expected.to_owned() as i64;
                     overtime -= StatusTime::from(Duration::minutes(exh));
-                    let missing_status = StatusDaily::builder().empty_with_overtime(
-                        overtime.to_owned()
-                    );
+                    let missing_status = StatusDaily::builder()
+                        .expected(StatusTime::from(Duration::minutes(exh)))
+                        .empty_with_overtime(overtime.to_owned());
                     entries.append(&mut [(d.date.unwrap().to_owned(), missing_status)].to_vec());
                 }
             }
@@ -155,8 +155,8 @@ impl StatusWeeklyBuilder {
 
         log::info!("totally {} working time and {} overtime", total, overtime);
 
-        // let i_32: i32 = total.duration.num_minutes().try_into()?;
-        let decimal: f64 = self.datetime_to_decimal(&total);
+        // payroll hours are based on the rounded work time
+        let decimal: f64 = decimal_hours(&rounded);
         let week = data.week.to_owned();
         let sw = StatusWeekly {
             week,
@@ -168,20 +168,10 @@ impl StatusWeeklyBuilder {
 
         Ok(sw)
     }
-
-    fn datetime_to_decimal(&self, total: &StatusTime) -> f64 {
-        let hours = total.hours as f64; //.try_into().unwrap();
-        let minutes = total.minutes as f64;
-        let md = minutes * (1.0 / 60.0);
-        hours + md
-    }
 }
 
 impl Display for StatusWeekly {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let zero_dr = Duration::minutes(0);
-        let overtime = self.overtime.clone();
-
         let width = 10;
 
         let line1 = format!(
@@ -193,11 +183,7 @@ impl Display for StatusWeekly {
         );
         let line2 = format!(" {0:->width$} | {0:->width$} | {0:->width$} | {0:->width$}", "");
 
-        let ot_fmt = match overtime.partial_cmp(&zero_dr.into()).unwrap() {
-            std::cmp::Ordering::Less => format!("-{}", overtime.mul(-1)).bright_red(),
-            std::cmp::Ordering::Equal => format!("{}", overtime).normal(),
-            std::cmp::Ordering::Greater => format!("+{}", overtime).bright_yellow(),
-        };
+        let ot_fmt = fmt_overtime(&self.overtime);
 
         let dc_fmt = format!("{:.2}", self.decimal);
         let t_fmt = format!("{}", self.total);
@@ -209,7 +195,19 @@ impl Display for StatusWeekly {
             ot_fmt,
             dc_fmt
         );
-        write!(f, "{}\n{}\n{}\n", line1, line2, line3)
+        write!(f, "{}\n{}\n{}\n", line1, line2, line3)?;
+        if self.entries.iter().any(|(_, s)| s.rounding_enabled()) {
+            writeln!(f, " {:width$} | {: >width$}", "Rounded", format!("{}", self.rounded()))?;
+        }
+
+        let projects = self.projects();
+        if projects.keys().any(|p| p.is_some()) {
+            write!(f, "\n {:width$} | {:width$}\n {2:->width$} | {2:->width$}\n", "Project", "Work time", "")?;
+            for (p, t) in projects.iter() {
+                writeln!(f, " {:width$} | {: >width$}", p.as_deref().unwrap_or("-"), format!("{}", t))?;
+            }
+        }
+        Ok(())
     }
 }
 
@@ -228,10 +226,6 @@ mod tests {
         let _ = env_logger::builder().is_test(true).try_init();
     }
 
-    fn test_env() {
-        env::set_var("RUST_TEST", "true");
-    }
-
     mod format {
         use colored::control::ShouldColorize;
 
@@ -262,6 +256,27 @@ mod tests {
             }
         }
 
+        #[test]
+        fn status_projects() {
+            logger();
+            let mut daily = StatusDaily::default();
+            daily.projects.insert(Some("foo".to_owned()), Duration::hours(3).into());
+            daily.projects.insert(None, Duration::hours(1).into());
+            let s = StatusWeekly {
+                week: 23,
+                total: StatusTime::from(Duration::hours(4)),
+                overtime: StatusTime::from(Duration::minutes(0)),
+                decimal: 4.0,
+                entries: [(Utc::now(), daily.to_owned()), (Utc::now(), daily)].to_vec(),
+            };
+
+            assert!(
+                format!("{}", s).ends_with(
+                    "\n Project    | Work time \n ---------- | ----------\n -          |      02:00\n foo        |      06:00\n"
+                )
+            );
+        }
+
         #[test]
         fn status_on_point() {
             logger();
@@ -309,7 +324,7 @@ mod tests {
     mod builder {
         use chrono::Utc;
 
-        use crate::Entries;
+        use crate::{ Entries, Rounding, RoundingMode, RoundingSettings };
 
         use super::*;
 
@@ -332,16 +347,19 @@ mod tests {
                         id: 1,
                         status: Status::Connect,
                         time: Utc.with_ymd_and_hms(2022, 3, day.into(), 0, 0, 0).unwrap(),
+                        ..Default::default()
                     },
                     Entry {
                         id: 2,
                         status: Status::Break,
                         time: Utc.with_ymd_and_hms(2022, 3, day.into(), 4, 0, 0).unwrap(),
+                        ..Default::default()
                     },
                     Entry {
                         id: 3,
                         status: Status::Connect,
                         time: Utc.with_ymd_and_hms(2022, 3, day.into(), 4, 30, 0).unwrap(),
+                        ..Default::default()
                     },
                     Entry {
                         id: 4,
@@ -354,6 +372,7 @@ mod tests {
                             end_minutes.into(),
                             0
                         ).unwrap(),
+                        ..Default::default()
                     },
                 ].to_vec(),
                 ..Default::default()
@@ -452,6 +471,80 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn should_not_expect_work_on_holiday() -> Result<(), TrackerError> {
+            logger();
+            let mut time_data = get_time_data(9, 45);
+            time_data[4].entries = Entries::default();
+            let mut settings = get_settings();
+            settings.workperday.holidays.insert(chrono::NaiveDate::from_ymd_opt(2022, 3, 11).unwrap(), 0);
+            let time_data_weekly = TimeDataWeekly {
+                entries: time_data,
+                week: 10,
+            };
+
+            let mut b = StatusWeekly::builder();
+            let s = b.data(time_data_weekly).settings(settings).build()?;
+
+            // friday is not missing, only monday to thursday are expected
+            assert_eq!(StatusTime::from(Duration::hours(32).add(Duration::minutes(15))), s.total);
+            assert_eq!(StatusTime::from(Duration::minutes(15)), s.overtime);
+            Ok(())
+        }
+
+        #[test]
+        fn should_attribute_night_shift_to_start_day() -> Result<(), TrackerError> {
+            logger();
+            let time = |day: u32, hour: u32, minute: u32| Utc.with_ymd_and_hms(2022, 3, day, hour, minute, 0).unwrap();
+            let night_shift = Entries {
+                data: [
+                    (Status::Connect, time(9, 22, 0)),
+                    (Status::Break, time(10, 2, 0)),
+                    (Status::Connect, time(10, 2, 30)),
+                    (Status::End, time(10, 6, 45)),
+                ]
+                    .iter()
+                    .enumerate()
+                    .map(|(n, (status, time))| Entry {
+                        id: (n + 1) as u8,
+                        status: status.to_owned(),
+                        time: time.to_owned(),
+                        ..Default::default()
+                    })
+                    .collect(),
+                ..Default::default()
+            };
+            let time_data_weekly = TimeDataWeekly {
+                entries: [
+                    TimeData {
+                        date: Some(time(9, 0, 0)),
+                        entries: night_shift,
+                        ..Default::default()
+                    },
+                    TimeData {
+                        date: Some(time(10, 0, 0)),
+                        ..Default::default()
+                    },
+                ].to_vec(),
+                week: 10,
+            };
+            let settings = Settings {
+                timezone: chrono_tz::UTC.into(),
+                ..get_settings()
+            };
+
+            let s = StatusWeekly::builder().data(time_data_weekly).settings(settings).build()?;
+
+            let (_, wednesday) = &s.entries[0];
+            assert_eq!("22:00", wednesday.start.as_ref().unwrap().clock());
+            assert_eq!("06:45 +1", wednesday.end.as_ref().unwrap().clock());
+            assert_eq!(StatusTime::from(Duration::hours(8)), wednesday.worktime);
+            // thursday has no work of its own
+            assert_eq!(StatusTime::from(Duration::hours(8)), s.total);
+            assert_eq!(StatusTime::from(Duration::hours(-8)), s.overtime);
+            Ok(())
+        }
+
         #[test]
         fn should_calculate_less() -> Result<(), TrackerError> {
             logger();
@@ -479,6 +572,59 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn should_output_days() -> Result<(), TrackerError> {
+            logger();
+            let time_data_weekly = TimeDataWeekly {
+                entries: get_time_data(8, 30),
+                week: 10,
+            };
+            let settings = Settings {
+                timezone: chrono_tz::UTC.into(),
+                ..get_settings()
+            };
+
+            let s = StatusWeekly::builder().data(time_data_weekly).settings(settings).build()?;
+            let output = s.output();
+            assert_eq!(7, output.days.len());
+            assert_eq!(s.total.duration.num_minutes(), output.worktime);
+            assert_eq!(output.overtime, output.days.iter().map(|d| d.overtime).sum::<i64>());
+
+            let monday = &output.days[0];
+            assert_eq!("2022-03-07", monday.date.unwrap().to_string());
+            assert_eq!("2022-03-07T00:00:00+00:00", monday.start.unwrap().to_rfc3339());
+            assert_eq!("2022-03-07T08:30:00+00:00", monday.end.unwrap().to_rfc3339());
+            assert_eq!(monday.worktime - monday.expected, monday.overtime);
+
+            let saturday = &output.days[5];
+            assert_eq!((None, 0), (saturday.start, saturday.worktime));
+            Ok(())
+        }
+
+        #[test]
+        fn should_calculate_decimal_of_rounded_worktime() -> Result<(), TrackerError> {
+            logger();
+            let time_data_weekly = TimeDataWeekly {
+                entries: get_time_data(6, 17),
+                week: 10,
+            };
+            let settings = Settings {
+                rounding: RoundingSettings {
+                    worktime: Rounding { minutes: 60, mode: RoundingMode::Down },
+                    ..Default::default()
+                },
+                ..get_settings()
+            };
+
+            let s = StatusWeekly::builder().data(time_data_weekly).settings(settings).build()?;
+
+            assert_eq!(StatusTime::from(Duration::hours(36).add(Duration::minutes(32))), s.total);
+            assert_eq!(StatusTime::from(Duration::hours(33)), s.rounded());
+            assert_eq!(33.0, s.decimal);
+            assert!(s.to_string().contains(" Rounded    |      33:00\n"));
+            Ok(())
+        }
+
         #[test]
         fn should_calculate_with_missing_day() -> Result<(), TrackerError> {
             logger();
@@ -540,9 +686,8 @@ mod tests {
         }
 
         #[test]
-        fn should_not_crash_on_format_table() -> Result<(), TrackerError> {
+        fn should_format_table() -> Result<(), TrackerError> {
             logger();
-            test_env();
             let time_data = get_time_data(10, 42);
             let settings = get_settings();
             let time_data_weekly = TimeDataWeekly {
@@ -552,7 +697,11 @@ mod tests {
 
             let mut b = StatusWeekly::builder();
             let s = b.data(time_data_weekly).settings(settings).build()?;
-            s.format_table();
+            let mut out = Vec::new();
+            s.format_table(&mut out)?;
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.contains(&format!("{:.2}", s.decimal)), "{}", out);
+            assert!(out.contains("Total week 10"), "{}", out);
             Ok(())
         }
     }
@@ -1,12 +1,11 @@
 use std::{
-    env,
-    fs::{File, OpenOptions},
+    fs::{self, File},
     path::PathBuf,
 };
 
 use serde::{Deserialize, Serialize};
 
-use crate::TrackerError;
+use crate::{write_json, TrackerError};
 
 #[derive(Default, Clone, Deserialize, Serialize, Debug)]
 pub struct Takeover {
@@ -25,22 +24,19 @@ pub struct TakeoverBuilder {
 }
 
 impl TakeoverBuilder {
-    pub fn file(&mut self) -> &mut Self {
-        let d = match env::var("RUST_TEST") {
-            Ok(_) => env::current_dir().unwrap_or_default(),
-            Err(_) => dirs::home_dir().unwrap_or_default(),
-        };
-        let f = d.join(".trackrs-takeover");
-        self.file = Some(f);
+    /// Takeover file, see [`crate::Settings::takeover_file`].
+    pub fn file(&mut self, file: PathBuf) -> &mut Self {
+        self.file = Some(file);
         self
     }
 
     pub fn set(&self, minutes: u16) -> Result<Takeover, TrackerError> {
-        if self.file.is_none() {
-            Err(TrackerError::TakeoverSetError {
+        let Some(file) = self.file.as_ref() else {
+            return Err(TrackerError::TakeoverSetError {
                 message: "takeover file not set".to_owned(),
-            })
-        } else if minutes == 0 {
+            });
+        };
+        if minutes == 0 {
             log::warn!("won't take over less than 0 minutes");
             Ok(Takeover::default())
         } else {
@@ -49,28 +45,34 @@ impl TakeoverBuilder {
             };
 
             log::debug!("takeover {} minutes next time", t.minutes.unwrap());
-            let w = OpenOptions::new()
-                .create(true)
-                .write(true)
-                .append(false)
-                .truncate(false)
-                .open(self.file.as_ref().unwrap())?;
-            serde_json::to_writer(w, &t)?;
+            if let Some(p) = file.parent() {
+                fs::create_dir_all(p)?;
+            }
+            write_json(file, &t, false)?;
             Ok(t)
         }
     }
 
-    pub fn get(&self) -> Result<Takeover, TrackerError> {
-        if self.file.is_none() {
-            Err(TrackerError::TakeoverGetError {
+    /// Remove queued takeover minutes, so they are not applied twice.
+    pub fn clear(&self) -> Result<(), TrackerError> {
+        let Some(file) = self.file.as_ref() else {
+            return Err(TrackerError::TakeoverSetError {
                 message: "takeover file not set".to_owned(),
-            })
-        } else {
-            let f = self.file.as_ref().unwrap();
+            });
+        };
+        if file.exists() {
+            log::debug!("clear takeover");
+            fs::remove_file(file)?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self) -> Result<Takeover, TrackerError> {
+        if let Some(f) = self.file.as_ref() {
             let mut t = Takeover::default();
             if f.exists() {
                 log::debug!("takeover was requested");
-                let f = File::open(&f)?;
+                let f = File::open(f)?;
                 t = serde_json::from_reader(f)?;
                 if t.minutes.is_none() || t.minutes.as_ref().unwrap() <= &0 {
                     t.minutes = None;
@@ -79,6 +81,10 @@ impl TakeoverBuilder {
                 log::debug!("no takeover requested");
             }
             Ok(t)
+        } else {
+            Err(TrackerError::TakeoverGetError {
+                message: "takeover file not set".to_owned(),
+            })
         }
     }
 }
@@ -88,15 +94,12 @@ mod tests {
 
     use crate::TrackerError;
 
-    use std::env;
     use std::fs::File;
 
     use std::io::Write;
 
     use crate::Takeover;
 
-    use std::env::set_current_dir;
-
     use std::fs;
 
     fn logger() {
@@ -104,10 +107,6 @@ mod tests {
         let _ = env_logger::builder().is_test(true).try_init();
     }
 
-    fn test_env() {
-        env::set_var("RUST_TEST", "true");
-    }
-
     mod get {
 
         use super::*;
@@ -115,11 +114,9 @@ mod tests {
         #[test]
         fn file_not_exists() -> Result<(), TrackerError> {
             logger();
-            test_env();
             let temp_dir = tempfile::tempdir()?;
-            set_current_dir(temp_dir.as_ref())?;
             let mut b = Takeover::builder();
-            let t = b.file().get()?;
+            let t = b.file(temp_dir.path().join("takeover.json")).get()?;
             assert!(t.minutes.is_none());
             Ok(())
         }
@@ -127,11 +124,9 @@ mod tests {
         #[test]
         fn file_contains_time() -> Result<(), TrackerError> {
             logger();
-            test_env();
             let file_content = "{\"minutes\":15}";
             let temp_dir = tempfile::tempdir()?;
-            set_current_dir(temp_dir.as_ref())?;
-            let time_file = temp_dir.path().join(".trackrs-takeover");
+            let time_file = temp_dir.path().join("takeover.json");
             let mut file = File::create(&time_file)?;
             file.write_all(file_content.as_bytes())?;
 
@@ -148,11 +143,9 @@ mod tests {
         #[test]
         fn file_contains_zero_time() -> Result<(), TrackerError> {
             logger();
-            test_env();
             let file_content = "{\"minutes\":0}";
             let temp_dir = tempfile::tempdir()?;
-            set_current_dir(temp_dir.as_ref())?;
-            let time_file = temp_dir.path().join(".trackrs-takeover");
+            let time_file = temp_dir.path().join("takeover.json");
             let mut file = File::create(&time_file)?;
             file.write_all(file_content.as_bytes())?;
 
@@ -172,10 +165,8 @@ mod tests {
         #[test]
         fn create_takeover_file() -> Result<(), TrackerError> {
             logger();
-            test_env();
             let temp_dir = tempfile::tempdir()?;
-            set_current_dir(temp_dir.as_ref())?;
-            let time_file = temp_dir.path().join(".trackrs-takeover");
+            let time_file = temp_dir.path().join("takeover.json");
             let mut b = Takeover::builder();
             b.file = Some(time_file.to_owned());
             let t = b.set(25)?;
@@ -189,15 +180,43 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn overwrite_takeover_file() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let time_file = temp_dir.path().join("takeover.json");
+            let mut b = Takeover::builder();
+            b.file = Some(time_file.to_owned());
+            b.set(125)?;
+            b.set(5)?;
+
+            let act_content = fs::read_to_string(time_file)?;
+            assert_eq!("{\"minutes\":5}", act_content);
+            Ok(())
+        }
+
+        #[test]
+        fn clear_takeover_file() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let time_file = temp_dir.path().join("takeover.json");
+            let mut b = Takeover::builder();
+            b.file = Some(time_file.to_owned());
+            b.set(25)?;
+            b.clear()?;
+
+            assert!(!time_file.exists());
+            assert!(b.get()?.minutes.is_none());
+            Ok(())
+        }
+
         #[test]
         fn create_empty_takeover() -> Result<(), TrackerError> {
             logger();
-            test_env();
             let temp_dir = tempfile::tempdir()?;
-            set_current_dir(temp_dir.as_ref())?;
-            let time_file = temp_dir.path().join(".trackrs-takeover");
+            let time_file = temp_dir.path().join("takeover.json");
             let mut b = Takeover::builder();
-            let t = b.file().set(0)?;
+            let t = b.file(time_file.to_owned()).set(0)?;
 
             assert!(!time_file.exists());
             assert!(t.minutes.is_none());
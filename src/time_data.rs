@@ -1,8 +1,29 @@
-use std::{ fs::{ self, File, OpenOptions }, io::BufReader, ops::Sub, path::PathBuf, str::FromStr };
-
-use chrono::{ DateTime, Duration, Utc };
-
-use crate::{ Entries, Entry, Status, Takeover, TrackerError, Upgrade };
+use std::{ fs::{ self, File }, io::BufReader, ops::Sub, path::PathBuf, str::FromStr };
+
+use chrono::{ DateTime, Duration, NaiveDate, TimeZone, Utc };
+
+use crate::{
+    AbsenceType,
+    Absences,
+    Entries,
+    Entry,
+    Settings,
+    Status,
+    StatusTime,
+    Takeover,
+    TrackerError,
+    Upgrade,
+    WorkTimeZone,
+    write_json,
+    FileLock,
+    LockHandle,
+};
+
+/// Time to wait for another process to release a time file.
+const DEFAULT_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// A session is only continued after midnight when its last entry is more recent than this.
+pub(crate) const SESSION_GAP: Duration = Duration::hours(12);
 
 pub type TimeDataResult = Result<TimeData, TrackerError>;
 pub type TimeDataWriteResult = Result<(), TrackerError>;
@@ -12,14 +33,26 @@ pub struct TimeData {
     pub entries: Entries,
     pub(super) file: PathBuf,
     pub(super) build: bool,
+    /// Day the file belongs to as midnight UTC, not the start of the day at the work location.
     pub date: Option<DateTime<Utc>>,
     pub takeover: Option<Takeover>,
+    /// Takeover file, no takeover is applied without one.
+    pub(super) takeover_file: Option<PathBuf>,
+    pub absence: Option<AbsenceType>,
+    /// Timezone entry times are listed in.
+    pub(super) timezone: WorkTimeZone,
+    /// Lock of the time file, held from [`TimeData::read_from_file`] until [`TimeData::write_to_file`].
+    pub(super) lock: LockHandle,
+    pub(super) lock_timeout: std::time::Duration,
 }
 
 impl TimeData {
     pub fn builder() -> TimeDataBuilder {
         TimeDataBuilder {
-            inner: TimeData::default(),
+            inner: TimeData {
+                lock_timeout: DEFAULT_LOCK_TIMEOUT,
+                ..Default::default()
+            },
             folder: PathBuf::default(),
             has_file: false,
         }
@@ -76,24 +109,228 @@ impl TimeData {
         &mut self,
         status: Status,
         time: DateTime<Utc>
+    ) -> Result<&mut Self, TrackerError> {
+        self.append_project(status, time, None, Vec::new())
+    }
+
+    /// Append an entry with project and tags, see [`TimeData::append`].
+    pub fn append_project(
+        &mut self,
+        status: Status,
+        time: DateTime<Utc>,
+        project: Option<String>,
+        tags: Vec<String>
     ) -> Result<&mut Self, TrackerError> {
         self.assert_build()?;
-        let last_id = match self.entries.data.last() {
-            Some(last) => last.id,
-            None => 0,
-        };
+        // entries are sorted by time, so a back dated entry is inserted at its position
+        let index = self.entries.data
+            .iter()
+            .position(|x| x.time > time)
+            .unwrap_or(self.entries.data.len());
+
+        self.assert_order(index, &status)?;
+
+        if index == self.entries.data.len() {
+            let last_id = match self.entries.data.last() {
+                Some(last) => last.id,
+                None => 0,
+            };
+
+            let entry = Entry::builder()
+                .id(last_id)
+                .status(status)
+                .time(time.to_utc())
+                .project(project, tags)
+                .build()?;
+            log::debug!("append time data: {:?}", entry);
+            self.entries.data.append(&mut [entry].to_vec());
+        } else {
+            let entry = Entry::builder()
+                .status(status)
+                .time(time.to_utc())
+                .project(project, tags)
+                .build()?;
+            log::debug!("insert time data at {}: {:?}", index, entry);
+            self.entries.data.insert(index, entry);
+            self.renumber();
+        }
+        Ok(self)
+    }
+
+    /// Attach a note to the current segment, which is the last connect entry.
+    pub fn note(&mut self, note: String) -> Result<&mut Self, TrackerError> {
+        self.assert_build()?;
+        match self.entries.data.iter_mut().rev().find(|x| x.status == Status::Connect) {
+            Some(e) => {
+                log::debug!("add note to entry {}", e.id);
+                e.note = Some(note);
+                Ok(self)
+            }
+            None => Err(TrackerError::TimeDataError {
+                message: "tracking not started, no segment to add the note to".to_owned(),
+            }),
+        }
+    }
 
-        let entry = Entry::builder().id(last_id).status(status).time(time.to_utc()).build()?;
-        log::debug!("append time data: {:?}", entry);
-        self.entries.data.append(&mut [entry].to_vec());
+    /// Set the note of the whole day.
+    pub fn day_note(&mut self, note: String) -> Result<&mut Self, TrackerError> {
+        self.assert_build()?;
+        self.entries.note = Some(note);
         Ok(self)
     }
 
-    pub fn assert_takeover(&mut self, time: DateTime<Utc>) -> Result<&mut Self, TrackerError> {
+    /// Change status and/or time of the entry with `id`.
+    ///
+    /// Entries are sorted by time and renumbered afterwards, the change is rejected if it breaks their order
+    /// or the time is not on the day of the file.
+    pub fn set(
+        &mut self,
+        id: u8,
+        status: Option<Status>,
+        time: Option<DateTime<Utc>>
+    ) -> Result<&mut Self, TrackerError> {
+        self.assert_build()?;
+        let mut data = self.entries.data.to_owned();
+        let entry = match data.iter_mut().find(|x| x.id == id) {
+            Some(e) => e,
+            None => {
+                return Err(TrackerError::TimeDataError {
+                    message: format!("entry {} not found", id),
+                });
+            }
+        };
+        if let Some(s) = status {
+            entry.status = s;
+        }
+        if let Some(t) = time {
+            entry.time = t;
+        }
+        log::debug!("set time data: {:?}", entry);
+        data.sort_by_key(|e| e.time);
+        if let Some(t) = time {
+            self.assert_day(&data, t)?;
+        }
+        self.assert_entries(&data)?;
+        self.entries.data = data;
+        Ok(self.renumber())
+    }
+
+    /// Delete the entry with `id` and renumber the remaining entries, unless it breaks their order.
+    pub fn delete(&mut self, id: u8) -> Result<&mut Self, TrackerError> {
+        self.assert_build()?;
+        let mut data = self.entries.data.to_owned();
+        data.retain(|x| x.id != id);
+        if data.len() == self.entries.data.len() {
+            return Err(TrackerError::TimeDataError {
+                message: format!("entry {} not found", id),
+            });
+        }
+        self.assert_entries(&data)?;
+        log::debug!("deleted entry {}", id);
+        self.entries.data = data;
+        Ok(self.renumber())
+    }
+
+    /// Renumber entry ids in order of the entries, starting with 1.
+    pub fn renumber(&mut self) -> &mut Self {
+        self.entries.data
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, e)| {
+                e.id = (i + 1) as u8;
+            });
+        self
+    }
+
+    /// Assert that an entry with `status` can be placed at `index` without breaking the
+    /// Connect/Break/End order of the day.
+    fn assert_order(&self, index: usize, status: &Status) -> Result<(), TrackerError> {
+        let previous = match index {
+            0 => None,
+            i => self.entries.data.get(i - 1).map(|x| &x.status),
+        };
+        let next = self.entries.data.get(index).map(|x| &x.status);
+        match TimeData::order(previous, status, next) {
+            Some(m) =>
+                Err(TrackerError::EntryOrderError {
+                    message: format!("{:?} not possible at this time, {}", status, m),
+                }),
+            None => Ok(()),
+        }
+    }
+
+    /// Assert the Connect/Break/End order of all entries, eg. after an edit.
+    fn assert_entries(&self, data: &[Entry]) -> Result<(), TrackerError> {
+        for (i, e) in data.iter().enumerate() {
+            let previous = match i {
+                0 => None,
+                i => data.get(i - 1).map(|x| &x.status),
+            };
+            let next = data.get(i + 1).map(|x| &x.status);
+            if let Some(m) = TimeData::order(previous, &e.status, next) {
+                return Err(TrackerError::EntryOrderError {
+                    message: format!("{:?} at {} not possible, {}", e.status, self.timezone.fixed(e.time), m),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Why `status` can not follow `previous` and precede `next`.
+    fn order(previous: Option<&Status>, status: &Status, next: Option<&Status>) -> Option<&'static str> {
+        match (previous, status, next) {
+            (_, Status::Takeover, _) => None,
+            (Some(Status::End), Status::Connect, _) => Some("day already ended"),
+            (None, Status::Break | Status::Disconnect | Status::End, _) => {
+                Some("tracking not started")
+            }
+            (Some(p), Status::Break | Status::Disconnect, _) if p != &Status::Connect => {
+                Some("tracking is not connected")
+            }
+            (_, Status::End, Some(n)) if n != &Status::Takeover => {
+                Some("entries exist after end")
+            }
+            (_, Status::Break | Status::Disconnect, Some(n)) if
+                n != &Status::Connect && n != &Status::End
+            => {
+                Some("next entry does not continue tracking")
+            }
+            _ => None,
+        }
+    }
+
+    /// Assert that `time` is on the day of the file, after midnight it has to continue the session before.
+    fn assert_day(&self, data: &[Entry], time: DateTime<Utc>) -> Result<(), TrackerError> {
+        let day = match self.date {
+            Some(d) => d.date_naive(),
+            None => {
+                return Ok(());
+            }
+        };
+        let start = self.timezone.utc(day.and_hms_opt(0, 0, 0).unwrap());
+        let end = day.succ_opt().and_then(|d| self.timezone.utc(d.and_hms_opt(0, 0, 0).unwrap()));
+        let continues = data
+            .iter()
+            .rev()
+            .find(|e| e.time < time)
+            .is_some_and(|e| e.status != Status::End && time - e.time < SESSION_GAP);
+
+        if start.is_some_and(|s| time < s) || (end.is_some_and(|e| time >= e) && !continues) {
+            return Err(TrackerError::TimeDataError {
+                message: format!("{} is not on {}", self.timezone.fixed(time), day),
+            });
+        }
+        Ok(())
+    }
+
+    /// Add a connect entry for the queued takeover minutes before `time`, returns the applied minutes.
+    ///
+    /// The takeover file is kept until [`TimeData::clear_takeover`], which is called once the day is written.
+    pub fn assert_takeover(&mut self, time: DateTime<Utc>) -> Result<Option<u16>, TrackerError> {
         self.assert_build()?;
-        if self.takeover.is_some() {
-            let m = self.takeover.as_ref().unwrap();
-            let time = time.sub(Duration::minutes(m.minutes.unwrap().try_into()?));
+        let minutes = self.takeover.take().and_then(|t| t.minutes);
+        if let Some(m) = minutes {
+            let time = time.sub(Duration::minutes(m.into()));
             let t_entry = Entry::builder()
                 .id(0)
                 .status(Status::Connect)
@@ -101,7 +338,15 @@ impl TimeData {
                 .build()?;
             self.entries.data.append(&mut [t_entry].to_vec());
         }
-        Ok(self)
+        Ok(minutes)
+    }
+
+    /// Remove the queued takeover minutes, so they are not applied twice.
+    pub fn clear_takeover(&self) -> Result<(), TrackerError> {
+        match self.takeover_file.to_owned() {
+            Some(f) => Takeover::builder().file(f).clear(),
+            None => Ok(()),
+        }
     }
 
     pub fn takeover(&mut self, takeover: Duration) -> Result<&mut Self, TrackerError> {
@@ -125,44 +370,89 @@ impl TimeData {
         Ok(self)
     }
 
+    /// Read the time file and lock it until [`TimeData::write_to_file`] or [`TimeData::unlock`].
     pub fn read_from_file(&mut self) -> Result<&mut Self, TrackerError> {
+        self.assert_build()?;
+        self.lock()?;
+        self.read_only()
+    }
+
+    /// Read the time file without locking it, for data which is not written back.
+    pub fn read_only(&mut self) -> Result<&mut Self, TrackerError> {
         self.assert_build()?;
         if self.file.exists() {
             let f = File::open(&self.file)?;
-            match Upgrade::to_v1(BufReader::new(f))? {
-                Some(res) => {
-                    self.entries = res;
-                }
+            let entries = match Upgrade::to_v1(BufReader::new(f))? {
+                Some(res) => res,
                 None => {
                     let f = File::open(&self.file)?;
-                    self.entries = serde_json::from_reader(BufReader::new(f))?;
+                    serde_json::from_reader(BufReader::new(f))?
                 }
-            }
+            };
+            self.entries = Upgrade::to_v3(Upgrade::to_v2(entries)?)?;
 
-            self.entries.data.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap());
+            self.entries.data.sort_by_key(|e| e.time);
         } else {
             log::info!("file not yet created: {:?}", &self.file);
             // invoke takeover
-            let mut b = Takeover::builder();
-            let t = b.file().get()?;
-            if t.minutes.is_some() {
-                self.takeover = Some(t);
+            if let Some(f) = self.takeover_file.to_owned() {
+                let t = Takeover::builder().file(f).get()?;
+                if t.minutes.is_some() {
+                    self.takeover = Some(t);
+                }
             }
         }
 
+        if let (Some(folder), Some(date)) = (self.file.parent(), self.date) {
+            self.absence = Absences::builder()
+                .folder(folder.to_path_buf().into())
+                .build()?
+                .get(&date.date_naive());
+        }
+
         Ok(self)
     }
 
-    pub fn write_to_file(&self) -> Result<(), TrackerError> {
+    /// Whether the last session of the day is still open at `time`, eg. a night shift past midnight.
+    pub fn continues_at(&self, time: DateTime<Utc>) -> bool {
+        match self.entries.data.last() {
+            Some(e) => e.status != Status::End && e.time <= time && time - e.time < SESSION_GAP,
+            None => false,
+        }
+    }
+
+    /// Expected work time of the day in minutes, credited absences expect no work.
+    pub fn expected(&self, settings: &Settings) -> u16 {
+        if self.absence.is_some_and(|a| a.credited()) {
+            return 0;
+        }
+        match (self.date, self.entries.data.first()) {
+            (Some(d), _) => settings.workperday.from_date(d).to_owned(),
+            (None, Some(e)) => settings.workperday.from(e.time).to_owned(),
+            (None, None) => 0,
+        }
+    }
+
+    pub fn write_to_file(&mut self) -> Result<(), TrackerError> {
         self.assert_build()?;
+        self.lock()?;
         log::debug!("write data to time file at {:?}", &self.file);
-        let w = OpenOptions::new()
-            .create(true)
-            .write(true)
-            .append(false)
-            .truncate(false)
-            .open(&self.file)?;
-        Ok(serde_json::to_writer(w, &self.entries)?)
+        let res = write_json(&self.file, &self.entries, false);
+        self.unlock();
+        res
+    }
+
+    /// Release the lock of the time file, eg. when it is only read.
+    pub fn unlock(&mut self) -> &mut Self {
+        self.lock = LockHandle(None);
+        self
+    }
+
+    fn lock(&mut self) -> Result<(), TrackerError> {
+        if self.lock.0.is_none() {
+            self.lock = LockHandle(Some(FileLock::acquire(&self.file, self.lock_timeout)?));
+        }
+        Ok(())
     }
 
     fn assert_build(&self) -> Result<(), TrackerError> {
@@ -176,6 +466,31 @@ impl TimeData {
     }
 }
 
+impl std::fmt::Display for TimeData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self.entries.data
+            .iter()
+            .map(|e| {
+                let time = StatusTime::entry(e, &self.timezone);
+                let mut line = format!("{:>3}  {:width$}{}", e.id, format!("{:?}", e.status), time, width = 12);
+                if let Some(p) = e.project.as_ref() {
+                    line.push_str(&format!("  {}", p));
+                }
+                e.tags.iter().for_each(|t| line.push_str(&format!(" #{}", t)));
+                if let Some(n) = e.note.as_ref() {
+                    line.push_str(&format!("  \"{}\"", n));
+                }
+                line
+            })
+            .collect::<Vec<String>>();
+        write!(f, "{}", lines.join("\n"))?;
+        if let Some(n) = self.entries.note.as_ref() {
+            write!(f, "\n\n     Note: {}", n)?;
+        }
+        Ok(())
+    }
+}
+
 pub struct TimeDataBuilder {
     inner: TimeData,
     folder: PathBuf,
@@ -223,17 +538,36 @@ impl TimeDataBuilder {
         self
     }
 
-    pub fn today(&mut self) -> &mut Self {
-        self.date(Utc::now())
+    /// Takeover file applied to a new day, see [`crate::Settings::takeover_file`].
+    pub fn takeover_file(&mut self, file: PathBuf) -> &mut Self {
+        self.inner.takeover_file = Some(file);
+        self
+    }
+
+    /// Timezone of the work location, see [`crate::Settings::timezone`].
+    pub fn timezone(&mut self, zone: WorkTimeZone) -> &mut Self {
+        self.inner.timezone = zone;
+        self
     }
 
-    pub fn date(&mut self, date: DateTime<Utc>) -> &mut Self {
-        let df = date.format("%Y%m%d");
+    /// Time to wait for a locked time file.
+    pub fn lock_timeout(&mut self, timeout: std::time::Duration) -> &mut Self {
+        self.inner.lock_timeout = timeout;
+        self
+    }
+
+    /// Time data of the day `date` falls into in its timezone.
+    pub fn date<Tz: TimeZone>(&mut self, date: DateTime<Tz>) -> &mut Self {
+        self.day(date.date_naive())
+    }
+
+    pub fn day(&mut self, day: NaiveDate) -> &mut Self {
+        let df = day.format("%Y%m%d");
         let file = self.folder.join(format!("{}.json", df));
         log::debug!("set time data file to: {:?}", &file);
         self.inner.file = file;
         self.has_file = true;
-        self.inner.date = Some(date);
+        self.inner.date = Some(Utc.from_utc_datetime(&day.and_hms_opt(0, 0, 0).unwrap()));
         self
     }
 
@@ -252,7 +586,7 @@ impl TimeDataBuilder {
 
 #[cfg(test)]
 mod tests {
-    use std::{ env, fs::{ self, File }, io::Write, ops::Add };
+    use std::{ fs::{ self, File }, io::Write, ops::Add };
 
     use chrono::{ Duration, TimeZone, Timelike };
 
@@ -286,7 +620,8 @@ mod tests {
     mod time_data {
         use std::{ io::Read, process::Command };
 
-        use chrono::{ NaiveTime, Utc };
+        use chrono::{ DateTime, NaiveTime, Utc };
+        use chrono_tz::UTC;
 
         use super::*;
 
@@ -418,7 +753,7 @@ mod tests {
             assert!(fs::metadata(&time_file)?.len() > initial_size);
 
             let file_content_update =
-                "{\"data\":[{\"id\":1,\"status\":\"Connect\",\"time\":\"2022-08-04T23:00:53.523319900Z\"},{\"id\":2,\"status\":\"End\",\"time\":\"2022-08-04T23:00:53.523332900Z\"},{\"id\":3,\"status\":\"End\",\"time\":\"2022-08-04T23:03:00Z\"}],\"version\":1}";
+                "{\"data\":[{\"id\":1,\"status\":\"Connect\",\"time\":\"2022-08-04T23:00:53.523319900Z\"},{\"id\":2,\"status\":\"End\",\"time\":\"2022-08-04T23:00:53.523332900Z\"},{\"id\":3,\"status\":\"End\",\"time\":\"2022-08-04T23:03:00Z\"}],\"version\":3}";
             let mut update_file = File::open(&time_file).unwrap();
             let mut data = vec![];
             update_file.read_to_end(&mut data)?;
@@ -428,6 +763,64 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn should_write_shrinking_time_data() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let day = Utc.with_ymd_and_hms(2022, 8, 4, 0, 0, 0).unwrap();
+            let mut time_data = TimeData::builder()
+                .folder(temp_dir.path().to_path_buf().into())
+                .date(day)
+                .build()?;
+            time_data
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()).unwrap())?
+                .append(Status::Break, day.with_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap()).unwrap())?
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(12, 30, 0).unwrap()).unwrap())?
+                .note("a note which makes the first write longer".to_owned())?
+                .write_to_file()?;
+            let initial_size = fs::metadata(&time_data.file)?.len();
+
+            time_data.delete(3)?.delete(2)?.write_to_file()?;
+            assert!(fs::metadata(&time_data.file)?.len() < initial_size);
+
+            let mut read = TimeData::builder()
+                .folder(temp_dir.path().to_path_buf().into())
+                .date(day)
+                .build()?;
+            read.read_only()?;
+            assert_eq!(1, read.entries.data.len());
+            // no temporary files are left next to the time file
+            let files = fs::read_dir(temp_dir.path())?
+                .filter(|f| f.as_ref().is_ok_and(|f| !f.file_name().to_string_lossy().ends_with(".lock")))
+                .count();
+            assert_eq!(1, files);
+            Ok(())
+        }
+
+        #[test]
+        fn should_lock_from_read_until_write() -> Result<(), TrackerError> {
+            logger();
+            let temp_dir = tempfile::tempdir()?;
+            let day = Utc.with_ymd_and_hms(2022, 8, 4, 0, 0, 0).unwrap();
+            let mut builder = TimeData::builder();
+            builder
+                .folder(temp_dir.path().to_path_buf().into())
+                .date(day)
+                .lock_timeout(std::time::Duration::from_millis(100));
+
+            let mut first = builder.build()?;
+            first.read_from_file()?.append(Status::Connect, day)?;
+
+            let mut second = builder.build()?;
+            let res = second.read_from_file().map(|_| ());
+            assert!(matches!(res, Err(TrackerError::LockError { .. })));
+
+            first.write_to_file()?;
+            second.read_from_file()?;
+            assert_eq!(1, second.entries.data.len());
+            Ok(())
+        }
+
         #[test]
         fn should_assert_break() -> Result<(), TrackerError> {
             logger();
@@ -665,6 +1058,166 @@ mod tests {
             Ok(())
         }
 
+        #[test]
+        fn should_insert_back_dated_entry() -> Result<(), TrackerError> {
+            logger();
+            let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+            let mut time_data = TimeData::builder().folder("".into()).date(day).build()?;
+            time_data
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()).unwrap())?
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(12, 30, 0).unwrap()).unwrap())?
+                .append(Status::Break, day.with_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap()).unwrap())?;
+
+            let status = time_data.entries.data
+                .iter()
+                .map(|x| (x.id, x.status.to_owned()))
+                .collect::<Vec<(u8, Status)>>();
+            assert_eq!(
+                [(1, Status::Connect), (2, Status::Break), (3, Status::Connect)].to_vec(),
+                status
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn should_reject_break_before_connect() -> Result<(), TrackerError> {
+            logger();
+            let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+            let mut time_data = TimeData::builder().folder("".into()).date(day).build()?;
+            time_data.append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()).unwrap())?;
+
+            let res = time_data.append(
+                Status::Break,
+                day.with_time(NaiveTime::from_hms_opt(7, 0, 0).unwrap()).unwrap()
+            );
+            assert!(res.is_err());
+            assert_eq!(
+                "entry order error: Break not possible at this time, tracking not started",
+                res.err().unwrap().to_string()
+            );
+            assert_eq!(1, time_data.entries.data.len());
+            Ok(())
+        }
+
+        #[test]
+        fn should_reject_entries_after_end() -> Result<(), TrackerError> {
+            logger();
+            let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+            let mut time_data = TimeData::builder().folder("".into()).date(day).build()?;
+            time_data
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()).unwrap())?
+                .append(Status::End, day.with_time(NaiveTime::from_hms_opt(16, 0, 0).unwrap()).unwrap())?;
+
+            assert!(
+                time_data
+                    .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(17, 0, 0).unwrap()).unwrap())
+                    .is_err()
+            );
+            assert!(
+                time_data
+                    .append(Status::End, day.with_time(NaiveTime::from_hms_opt(15, 0, 0).unwrap()).unwrap())
+                    .is_err()
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn should_set_entry() -> Result<(), TrackerError> {
+            logger();
+            let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+            let at = |h, m| day.with_time(NaiveTime::from_hms_opt(h, m, 0).unwrap()).unwrap();
+            let mut time_data = TimeData::builder().folder("".into()).timezone(UTC.into()).date(day).build()?;
+            time_data
+                .append(Status::Connect, at(8, 0))?
+                .append(Status::Break, at(12, 0))?
+                .append(Status::Connect, at(12, 30))?
+                .set(2, None, Some(at(12, 45)))?
+                .set(3, Some(Status::Disconnect), None)?;
+
+            let status = time_data.entries.data
+                .iter()
+                .map(|x| (x.id, x.status.to_owned()))
+                .collect::<Vec<(u8, Status)>>();
+            assert_eq!(
+                [(1, Status::Connect), (2, Status::Connect), (3, Status::Disconnect)].to_vec(),
+                status
+            );
+            assert!(time_data.set(4, Some(Status::End), None).is_err());
+            Ok(())
+        }
+
+        #[test]
+        fn should_not_set_entry_out_of_order() -> Result<(), TrackerError> {
+            logger();
+            let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+            let at = |h, m| day.with_time(NaiveTime::from_hms_opt(h, m, 0).unwrap()).unwrap();
+            let mut time_data = TimeData::builder().folder("".into()).timezone(UTC.into()).date(day).build()?;
+            time_data
+                .append(Status::Connect, at(8, 0))?
+                .append(Status::Break, at(12, 0))?
+                .append(Status::Connect, at(12, 30))?
+                .append(Status::End, at(16, 0))?;
+            let entries = |t: &TimeData| {
+                t.entries.data
+                    .iter()
+                    .map(|x| (x.id, x.status.to_owned(), x.time))
+                    .collect::<Vec<(u8, Status, DateTime<Utc>)>>()
+            };
+            let before = entries(&time_data);
+
+            // break before the start
+            assert!(time_data.set(1, None, Some(at(13, 0))).is_err());
+            assert!(time_data.set(3, Some(Status::End), None).is_err());
+            // not on the day of the file
+            assert!(time_data.set(1, None, Some(day - Duration::minutes(30))).is_err());
+            assert!(time_data.set(4, None, Some(day + Duration::hours(25))).is_err());
+            assert_eq!(before, entries(&time_data));
+
+            // a session continued after midnight
+            time_data.set(4, None, Some(day + Duration::hours(24) + Duration::minutes(15)))?;
+            Ok(())
+        }
+
+        #[test]
+        fn should_delete_entry() -> Result<(), TrackerError> {
+            logger();
+            let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+            let mut time_data = TimeData::builder().folder("".into()).date(day).build()?;
+            time_data
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()).unwrap())?
+                .append(Status::Break, day.with_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap()).unwrap())?
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(12, 30, 0).unwrap()).unwrap())?
+                .delete(2)?;
+
+            assert_eq!(2, time_data.entries.data.len());
+            assert_eq!(2, time_data.entries.data.last().unwrap().id);
+            assert!(time_data.delete(3).is_err());
+
+            // the break would start the day
+            time_data.append(Status::Break, day.with_time(NaiveTime::from_hms_opt(13, 0, 0).unwrap()).unwrap())?;
+            time_data.delete(2)?;
+            assert!(time_data.delete(1).is_err());
+            assert_eq!(2, time_data.entries.data.len());
+            Ok(())
+        }
+
+        #[test]
+        fn should_list_entries() -> Result<(), TrackerError> {
+            logger();
+            let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+            let mut time_data = TimeData::builder().folder("".into()).date(day).build()?;
+            time_data
+                .append(Status::Connect, day.with_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()).unwrap())?
+                .append(Status::Break, day.with_time(NaiveTime::from_hms_opt(12, 0, 0).unwrap()).unwrap())?;
+
+            let lines = format!("{}", time_data);
+            let lines = lines.split('\n').collect::<Vec<&str>>();
+            assert_eq!(2, lines.len());
+            assert!(lines[0].starts_with("  1  Connect     "));
+            assert!(lines[1].starts_with("  2  Break       "));
+            Ok(())
+        }
+
         #[test]
         fn should_create_takeover_entry() -> Result<(), TrackerError> {
             logger();
@@ -698,7 +1251,7 @@ mod tests {
         }
 
         mod takeover {
-            use chrono::{ DateTime, Utc };
+            use chrono::Utc;
 
             use super::*;
 
@@ -709,9 +1262,7 @@ mod tests {
             impl test_context::TestContext for TakeoverContext {
                 fn setup() -> TakeoverContext {
                     logger();
-                    env::set_var("RUST_TEST", "true");
                     let temp_dir = tempfile::tempdir().unwrap();
-                    env::set_current_dir(&temp_dir).unwrap();
                     TakeoverContext { temp_dir }
                 }
 
@@ -728,7 +1279,7 @@ mod tests {
                 let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
 
                 let file_content = "{\"minutes\":15}";
-                let takeover_file = ctx.temp_dir.path().join(".trackrs-takeover");
+                let takeover_file = ctx.temp_dir.path().join("takeover.json");
                 let mut file = File::create(&takeover_file)?;
                 file.write_all(file_content.as_bytes())?;
 
@@ -736,13 +1287,14 @@ mod tests {
 
                 let mut time_data = TimeData::builder()
                     .folder(ctx.temp_dir.as_ref().to_owned().into())
+                    .takeover_file(takeover_file.to_owned())
                     .date(day)
                     .build()?;
+                let takeover = time_data.read_from_file()?.assert_takeover(
+                    day.with_time(NaiveTime::from_hms_opt(2, 16, 0).unwrap()).unwrap()
+                )?;
+                assert!(takeover.is_some());
                 time_data
-                    .read_from_file()?
-                    .assert_takeover(
-                        day.with_time(NaiveTime::from_hms_opt(2, 16, 0).unwrap()).unwrap()
-                    )?
                     .append(
                         Status::Connect,
                         day.with_time(NaiveTime::from_hms_opt(2, 16, 0).unwrap()).unwrap()
@@ -752,7 +1304,10 @@ mod tests {
                         day.with_time(NaiveTime::from_hms_opt(5, 0, 0).unwrap()).unwrap()
                     )?
                     .write_to_file()?;
+                assert!(takeover_file.exists(), "takeover should be kept until cleared");
+                time_data.clear_takeover()?;
                 assert!(&time_file.exists(), "time file should exist");
+                assert!(!takeover_file.exists(), "takeover should be cleared after use");
 
                 let takeover_time = time_data.entries.data[0].to_owned();
                 let first_connect = time_data.entries.data[1].to_owned();
@@ -782,7 +1337,7 @@ mod tests {
                 let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
 
                 let file_content = "{\"minutes\":15}";
-                let takeover_file = ctx.temp_dir.path().join(".trackrs-takeover");
+                let takeover_file = ctx.temp_dir.path().join("takeover.json");
                 let mut file = File::create(&takeover_file)?;
                 file.write_all(file_content.as_bytes())?;
 
@@ -790,26 +1345,21 @@ mod tests {
 
                 let mut time_data = TimeData::builder()
                     .folder(ctx.temp_dir.as_ref().to_owned().into())
-                    .date(DateTime::from(day))
+                    .takeover_file(takeover_file.to_owned())
+                    .date(day)
                     .build()?;
+                let takeover = time_data.read_from_file()?.assert_takeover(
+                    day.with_time(NaiveTime::from_hms_opt(2, 15, 0).unwrap()).unwrap()
+                )?;
+                assert!(takeover.is_some());
                 time_data
-                    .read_from_file()?
-                    .assert_takeover(
-                        DateTime::from(
-                            day.with_time(NaiveTime::from_hms_opt(2, 15, 0).unwrap()).unwrap()
-                        )
-                    )?
                     .append(
                         Status::Connect,
-                        DateTime::from(
-                            day.with_time(NaiveTime::from_hms_opt(2, 15, 0).unwrap()).unwrap()
-                        )
+                        day.with_time(NaiveTime::from_hms_opt(2, 15, 0).unwrap()).unwrap()
                     )?
                     .append(
                         Status::End,
-                        DateTime::from(
-                            day.with_time(NaiveTime::from_hms_opt(2, 45, 0).unwrap()).unwrap()
-                        )
+                        day.with_time(NaiveTime::from_hms_opt(2, 45, 0).unwrap()).unwrap()
                     )?
                     .write_to_file()?;
                 assert!(&time_file.exists());
@@ -844,7 +1394,7 @@ mod tests {
                 let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
 
                 let file_content = "{\"minutes\":95}";
-                let takeover_file = ctx.temp_dir.path().join(".trackrs-takeover");
+                let takeover_file = ctx.temp_dir.path().join("takeover.json");
                 let mut file = File::create(&takeover_file)?;
                 file.write_all(file_content.as_bytes())?;
 
@@ -852,13 +1402,14 @@ mod tests {
 
                 let mut time_data = TimeData::builder()
                     .folder(ctx.temp_dir.as_ref().to_owned().into())
+                    .takeover_file(takeover_file.to_owned())
                     .date(day)
                     .build()?;
+                let takeover = time_data.read_from_file()?.assert_takeover(
+                    day.with_time(NaiveTime::from_hms_opt(2, 15, 0).unwrap()).unwrap()
+                )?;
+                assert!(takeover.is_some());
                 time_data
-                    .read_from_file()?
-                    .assert_takeover(
-                        day.with_time(NaiveTime::from_hms_opt(2, 15, 0).unwrap()).unwrap()
-                    )?
                     .append(
                         Status::Connect,
                         day.with_time(NaiveTime::from_hms_opt(2, 15, 0).unwrap()).unwrap()
@@ -882,6 +1433,66 @@ mod tests {
                 );
                 Ok(())
             }
+
+            #[test_context::test_context(TakeoverContext)]
+            #[test]
+            #[serial]
+            fn should_keep_takeover_on_failed_append(ctx: &mut TakeoverContext) -> Result<(), TrackerError> {
+                let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+                let takeover_file = ctx.temp_dir.path().join("takeover.json");
+                fs::write(&takeover_file, "{\"minutes\":15}")?;
+
+                let mut time_data = TimeData::builder()
+                    .folder(ctx.temp_dir.as_ref().to_owned().into())
+                    .takeover_file(takeover_file.to_owned())
+                    .date(day)
+                    .build()?;
+                let takeover = time_data.read_from_file()?.assert_takeover(
+                    day.with_time(NaiveTime::from_hms_opt(8, 0, 0).unwrap()).unwrap()
+                )?;
+                assert_eq!(Some(15), takeover);
+
+                let res = time_data.append(
+                    Status::Break,
+                    day.with_time(NaiveTime::from_hms_opt(7, 0, 0).unwrap()).unwrap()
+                );
+                assert!(res.is_err());
+                assert!(takeover_file.exists(), "takeover should be kept when the entry is not written");
+                Ok(())
+            }
+        }
+    }
+
+    mod session {
+        use chrono::Utc;
+
+        use crate::Entries;
+
+        use super::*;
+
+        #[test]
+        fn should_continue_open_session() {
+            let start = Utc.with_ymd_and_hms(2022, 2, 2, 22, 0, 0).unwrap();
+            let mut time_data = TimeData {
+                entries: Entries {
+                    data: vec![Entry { id: 1, status: Status::Connect, time: start, ..Default::default() }],
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            assert!(time_data.continues_at(start.add(Duration::hours(8))));
+            assert!(!time_data.continues_at(start.add(Duration::hours(12))));
+            assert!(!time_data.continues_at(start.add(Duration::hours(-1))));
+
+            time_data.entries.data.push(Entry {
+                id: 2,
+                status: Status::End,
+                time: start.add(Duration::hours(8)),
+                ..Default::default()
+            });
+            assert!(!time_data.continues_at(start.add(Duration::hours(9))));
+            assert!(!TimeData::default().continues_at(start));
         }
     }
 }
@@ -0,0 +1,200 @@
+use chrono::{ DateTime, NaiveDate, Utc };
+
+use crate::{ Folder, TimeData, TimeDataRangeBuilder, TrackerError };
+
+#[derive(Default, Clone)]
+pub struct TimeDataMonthly {
+    pub entries: Vec<TimeData>,
+    pub month: u32,
+    pub year: i32,
+}
+
+impl TimeDataMonthly {
+    pub fn builder() -> TimeDataMonthlyBuilder {
+        TimeDataMonthlyBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct TimeDataMonthlyBuilder {
+    inner: TimeDataMonthly,
+    folder: Option<Folder>,
+    month: Option<i8>,
+    year: Option<u16>,
+    dates: Option<Vec<DateTime<Utc>>>,
+}
+
+impl TimeDataMonthlyBuilder {
+    pub fn folder(&mut self, folder: Folder) -> &mut Self {
+        log::debug!("set time data folder to: {:?}", &folder);
+        self.folder = Some(folder);
+        self
+    }
+
+    pub fn year(&mut self, year: u16) -> &mut Self {
+        log::debug!("set year to: {:?}", year);
+        self.year = Some(year);
+        self
+    }
+
+    /// Set month either absolute (1 to 12) or relative to `current_month` (0, -1, ...).
+    pub fn month(&mut self, month: &i8, current_month: u32) -> &mut Self {
+        log::debug!("set month to: {:?}", month);
+        self.month = match month > &0 {
+            true => Some(month.to_owned()),
+            false => {
+                let cm: i8 = current_month.try_into().unwrap();
+                Some(cm + month)
+            }
+        };
+        self
+    }
+
+    pub fn build(&mut self) -> Result<TimeDataMonthly, TrackerError> {
+        if self.year.is_none() {
+            return Err(TrackerError::TimeDataError {
+                message: "year not defined".to_owned(),
+            });
+        }
+        if self.month.is_none() {
+            return Err(TrackerError::TimeDataError {
+                message: "month not defined".to_owned(),
+            });
+        }
+        if self.month.unwrap() > 12 {
+            return Err(TrackerError::TimeDataError {
+                message: format!("month {} does not exist", self.month.unwrap()),
+            });
+        }
+        if self.folder.is_none() {
+            return Err(TrackerError::TimeDataError {
+                message: "folder is not defined".to_owned(),
+            });
+        }
+
+        self.assert_relative_month().set_dates()?.set_files()?;
+        Ok(self.inner.clone())
+    }
+
+    fn assert_relative_month(&mut self) -> &mut Self {
+        let month = self.month.unwrap();
+        if month < 1 {
+            self.month = Some(month + 12);
+            self.year(self.year.unwrap() - 1);
+            self.assert_relative_month();
+        }
+        self
+    }
+
+    fn set_files(&mut self) -> Result<&mut Self, TrackerError> {
+        let dates = match self.dates.to_owned() {
+            Some(d) => d,
+            None => {
+                return Err(TrackerError::TimeDataError {
+                    message: "dates are not defined".to_owned(),
+                });
+            }
+        };
+
+        self.inner.entries = TimeDataRangeBuilder::read(self.folder.as_ref().unwrap(), &dates)?;
+        Ok(self)
+    }
+
+    fn set_dates(&mut self) -> Result<&mut Self, TrackerError> {
+        let year: i32 = self.year.unwrap().into();
+        let month: u32 = self.month.unwrap().try_into()?;
+
+        let first = NaiveDate::from_ymd_opt(year, month, 1).unwrap();
+        let last = match month {
+            12 => NaiveDate::from_ymd_opt(year + 1, 1, 1),
+            _ => NaiveDate::from_ymd_opt(year, month + 1, 1),
+        }
+            .unwrap()
+            .pred_opt()
+            .unwrap();
+
+        self.inner.month = month;
+        self.inner.year = year;
+        self.dates = Some(TimeDataRangeBuilder::dates(first, last));
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Datelike;
+
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    mod builder {
+        use super::*;
+
+        #[test]
+        fn should_set() {
+            let mut builder = TimeDataMonthly::builder();
+            builder.year(2022).month(&2, 5);
+
+            assert_eq!(Some(2), builder.month);
+        }
+
+        #[test]
+        fn should_set_month_by_sub() {
+            let mut builder = TimeDataMonthly::builder();
+            builder.year(2022).month(&-1, 5);
+
+            assert_eq!(Some(4), builder.month);
+        }
+
+        #[test]
+        fn should_set_month_of_last_year() -> Result<(), TrackerError> {
+            let mut builder = TimeDataMonthly::builder();
+            builder.year(2022).month(&-14, 2).assert_relative_month().set_dates()?;
+
+            assert_eq!(Some(12), builder.month);
+            assert_eq!(Some(2020), builder.year);
+            let dates = builder.dates.unwrap();
+            assert_eq!(31, dates.len());
+            assert_eq!(2020, dates.first().unwrap().year());
+            Ok(())
+        }
+
+        #[test]
+        fn no_month() {
+            let mut builder = TimeDataMonthly::builder();
+            let res = builder.year(2022).build();
+            assert!(res.is_err());
+            assert_eq!("time data error: month not defined", res.err().unwrap().to_string());
+        }
+
+        #[test]
+        fn invalid_month() {
+            let mut builder = TimeDataMonthly::builder();
+            let res = builder.year(2022).month(&13, 1).build();
+            assert!(res.is_err());
+            assert_eq!("time data error: month 13 does not exist", res.err().unwrap().to_string());
+        }
+
+        #[test]
+        fn should_build() -> Result<(), TrackerError> {
+            logger();
+            let mut builder = TimeDataMonthly::builder();
+            let t = builder.folder(Folder::default()).year(2024).month(&2, 3).build()?;
+            assert_eq!(29, t.entries.len());
+            assert_eq!(2, t.month);
+            assert_eq!(2024, t.year);
+
+            let first = t.entries.first().unwrap();
+            let last = t.entries.last().unwrap();
+
+            assert_eq!("20240201.json", first.file.to_str().unwrap());
+            assert_eq!("20240229.json", last.file.to_str().unwrap());
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use std::{ collections::BTreeMap, fs, path::{ Path, PathBuf } };
+
+use chrono::{ DateTime, NaiveDate, TimeZone, Utc };
+
+use crate::{ Folder, TimeData, TrackerError };
+
+#[derive(Default, Clone)]
+pub struct TimeDataRange {
+    pub entries: Vec<TimeData>,
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+}
+
+impl TimeDataRange {
+    pub fn builder() -> TimeDataRangeBuilder {
+        TimeDataRangeBuilder::default()
+    }
+}
+
+#[derive(Default)]
+pub struct TimeDataRangeBuilder {
+    inner: TimeDataRange,
+    folder: Option<Folder>,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    dates: Option<Vec<DateTime<Utc>>>,
+}
+
+impl TimeDataRangeBuilder {
+    pub fn folder(&mut self, folder: Folder) -> &mut Self {
+        log::debug!("set time data folder to: {:?}", &folder);
+        self.folder = Some(folder);
+        self
+    }
+
+    /// First day of the range, inclusive.
+    pub fn from(&mut self, from: NaiveDate) -> &mut Self {
+        log::debug!("set from to: {:?}", from);
+        self.from = Some(from);
+        self
+    }
+
+    /// Last day of the range, inclusive.
+    pub fn to(&mut self, to: NaiveDate) -> &mut Self {
+        log::debug!("set to to: {:?}", to);
+        self.to = Some(to);
+        self
+    }
+
+    pub fn build(&mut self) -> Result<TimeDataRange, TrackerError> {
+        let (from, to) = match (self.from, self.to) {
+            (Some(f), Some(t)) => (f, t),
+            _ => {
+                return Err(TrackerError::TimeDataError {
+                    message: "range not defined".to_owned(),
+                });
+            }
+        };
+        if from > to {
+            return Err(TrackerError::TimeDataError {
+                message: format!("range start {} is after range end {}", from, to),
+            });
+        }
+        if self.folder.is_none() {
+            return Err(TrackerError::TimeDataError {
+                message: "folder is not defined".to_owned(),
+            });
+        }
+
+        self.inner.from = from;
+        self.inner.to = to;
+        self.set_dates().set_files()?;
+        Ok(self.inner.clone())
+    }
+
+    fn set_dates(&mut self) -> &mut Self {
+        self.dates = Some(TimeDataRangeBuilder::dates(self.from.unwrap(), self.to.unwrap()));
+        self
+    }
+
+    fn set_files(&mut self) -> Result<&mut Self, TrackerError> {
+        let dates = match self.dates.to_owned() {
+            Some(d) => d,
+            None => {
+                return Err(TrackerError::TimeDataError {
+                    message: "dates are not defined".to_owned(),
+                });
+            }
+        };
+        self.inner.entries = TimeDataRangeBuilder::read(self.folder.as_ref().unwrap(), &dates)?;
+        Ok(self)
+    }
+
+    /// Every day between `from` and `to`, both inclusive.
+    pub(crate) fn dates(from: NaiveDate, to: NaiveDate) -> Vec<DateTime<Utc>> {
+        from.iter_days()
+            .take_while(|d| d <= &to)
+            .map(|d| {
+                let d = Utc.from_utc_datetime(&d.and_hms_opt(0, 0, 0).unwrap());
+                log::debug!("add {:?} to dates", d);
+                d
+            })
+            .collect()
+    }
+
+    /// Time files of `folder` by their day.
+    pub(crate) fn files(folder: &Path) -> Result<BTreeMap<NaiveDate, PathBuf>, TrackerError> {
+        let mut files = BTreeMap::new();
+        if !folder.exists() {
+            return Ok(files);
+        }
+        for entry in fs::read_dir(folder)? {
+            let path = entry?.path();
+            let date = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".json"))
+                .and_then(|n| NaiveDate::parse_from_str(n, "%Y%m%d").ok());
+            if let Some(d) = date {
+                files.insert(d, path);
+            }
+        }
+        Ok(files)
+    }
+
+    /// Read the time data files of all `dates` from `folder`.
+    pub(crate) fn read(
+        folder: &Folder,
+        dates: &[DateTime<Utc>]
+    ) -> Result<Vec<TimeData>, TrackerError> {
+        let mut entries: Vec<TimeData> = Default::default();
+        for d in dates.iter() {
+            let mut t = TimeData::builder().folder(folder.to_owned()).day(d.date_naive()).build()?;
+            t.read_only()?;
+            entries.push(t);
+        }
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn logger() {
+        // std::env::set_var("RUST_LOG", "debug");
+        let _ = env_logger::builder().is_test(true).try_init();
+    }
+
+    mod builder {
+        use chrono::Datelike;
+
+        use super::*;
+
+        #[test]
+        fn no_range() {
+            let mut builder = TimeDataRange::builder();
+            let res = builder.from(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()).build();
+            assert!(res.is_err());
+            assert_eq!("time data error: range not defined", res.err().unwrap().to_string());
+        }
+
+        #[test]
+        fn invalid_range() {
+            let mut builder = TimeDataRange::builder();
+            let res = builder
+                .folder(Folder::default())
+                .from(NaiveDate::from_ymd_opt(2022, 2, 1).unwrap())
+                .to(NaiveDate::from_ymd_opt(2022, 1, 1).unwrap())
+                .build();
+            assert!(res.is_err());
+            assert_eq!(
+                "time data error: range start 2022-02-01 is after range end 2022-01-01",
+                res.err().unwrap().to_string()
+            );
+        }
+
+        #[test]
+        fn should_set_dates_across_year() {
+            let dates = TimeDataRangeBuilder::dates(
+                NaiveDate::from_ymd_opt(2021, 12, 30).unwrap(),
+                NaiveDate::from_ymd_opt(2022, 1, 2).unwrap()
+            );
+            assert_eq!(4, dates.len());
+            assert_eq!(2021, dates.first().unwrap().year());
+            assert_eq!(2, dates.last().unwrap().day());
+        }
+
+        #[test]
+        fn should_build() -> Result<(), TrackerError> {
+            logger();
+            let mut builder = TimeDataRange::builder();
+            let t = builder
+                .folder(Folder::default())
+                .from(NaiveDate::from_ymd_opt(2022, 1, 17).unwrap())
+                .to(NaiveDate::from_ymd_opt(2022, 2, 3).unwrap())
+                .build()?;
+            assert_eq!(18, t.entries.len());
+
+            let first = t.entries.first().unwrap();
+            let last = t.entries.last().unwrap();
+
+            assert_eq!("20220117.json", first.file.to_str().unwrap());
+            assert_eq!("20220203.json", last.file.to_str().unwrap());
+            Ok(())
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use chrono::{ DateTime, Datelike, IsoWeek, NaiveDate, TimeZone, Utc, Weekday };
 
-use crate::{ Folder, TimeData, TrackerError };
+use crate::{ Folder, TimeData, TimeDataRangeBuilder, TrackerError, WorkTimeZone };
 
 #[derive(Default, Clone)]
 pub struct TimeDataWeekly {
@@ -49,6 +49,12 @@ impl TimeDataWeeklyBuilder {
         self
     }
 
+    /// Set the year and `week` relative to the current week at the work location.
+    pub fn current_week(&mut self, week: &i8, zone: &WorkTimeZone) -> &mut Self {
+        let today = zone.today();
+        self.year(today.year().try_into().unwrap()).week(week, today.iso_week())
+    }
+
     pub fn build(&mut self) -> Result<TimeDataWeekly, TrackerError> {
         if self.year.is_none() {
             return Err(TrackerError::TimeDataError {
@@ -94,17 +100,7 @@ impl TimeDataWeeklyBuilder {
             }
         };
 
-        let mut entries: Vec<TimeData> = Default::default();
-
-        dates.iter().for_each(|d| {
-            let mut b = TimeData::builder();
-            let f = self.folder.as_ref().unwrap().to_owned();
-            let mut t = b.folder(f).date(d.to_owned()).build().unwrap();
-            t.read_from_file().unwrap();
-            let mut v = [t].to_vec();
-            entries.append(v.as_mut());
-        });
-        self.inner.entries = entries;
+        self.inner.entries = TimeDataRangeBuilder::read(self.folder.as_ref().unwrap(), &dates)?;
         Ok(self)
     }
 
@@ -194,6 +190,19 @@ mod tests {
             assert_eq!(w, builder.week.unwrap());
         }
 
+        #[test]
+        fn should_set_current_week_at_work_location() {
+            let zone = WorkTimeZone::from(chrono_tz::Pacific::Kiritimati);
+            let today = zone.today();
+
+            let mut builder = TimeDataWeekly::builder();
+            builder.current_week(&-1, &zone);
+
+            let w: i8 = today.iso_week().week().try_into().unwrap();
+            assert_eq!(Some(w - 1), builder.week);
+            assert_eq!(Some(today.year().try_into().unwrap()), builder.year);
+        }
+
         #[test]
         fn no_year() {
             let mut builder = TimeDataWeekly::builder();
@@ -0,0 +1,269 @@
+use std::str::FromStr;
+
+use chrono::{ DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc };
+
+use crate::{ TrackerError, WorkTimeZone };
+
+/// Time provided by the user to back- or postdate an entry.
+///
+/// Supported expressions are `now`, a time of today (`08:15`), a relative offset
+/// (`-10m`, `+1h30m`), a day keyword with time (`yesterday 17:30`) or a full date with time (`2022-02-02 08:15`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeExpression {
+    Now,
+    Relative(Duration),
+    Time {
+        days: i64,
+        time: NaiveTime,
+    },
+    DateTime(NaiveDateTime),
+}
+
+impl TimeExpression {
+    /// Resolve the expression relative to `now` at the work location.
+    pub fn resolve(&self, now: DateTime<Utc>, zone: &WorkTimeZone) -> Result<DateTime<Utc>, TrackerError> {
+        let local = match self {
+            TimeExpression::Now => {
+                return Ok(now);
+            }
+            TimeExpression::Relative(d) => {
+                return now.checked_add_signed(d.to_owned()).ok_or_else(|| TrackerError::TimeExpressionError {
+                    message: "relative time is out of range".to_owned(),
+                });
+            }
+            TimeExpression::Time { days, time } => {
+                let date = zone.date(now) + Duration::days(*days);
+                date.and_time(*time)
+            }
+            TimeExpression::DateTime(dt) => dt.to_owned(),
+        };
+        zone.utc(local).ok_or_else(|| TrackerError::TimeExpressionError {
+            message: format!("{} does not exist in {}", local, zone),
+        })
+    }
+
+    /// Parse a day, either `today`, `yesterday`, `tomorrow` or `YYYY-MM-DD`, relative to `today`.
+    pub fn day(s: &str, today: NaiveDate) -> Result<NaiveDate, TrackerError> {
+        match TimeExpression::from_str(&format!("{} 00:00", s))? {
+            TimeExpression::Time { days, time: _ } => Ok(today + Duration::days(days)),
+            TimeExpression::DateTime(dt) => Ok(dt.date()),
+            _ =>
+                Err(TrackerError::TimeExpressionError {
+                    message: format!("invalid day: {}", s),
+                }),
+        }
+    }
+
+    fn parse_relative(s: &str) -> Result<Duration, TrackerError> {
+        let err = || TrackerError::TimeExpressionError {
+            message: format!("invalid relative time: {}", s),
+        };
+        let (sign, rest) = match s.split_at(1) {
+            ("-", r) => (-1, r),
+            ("+", r) => (1, r),
+            _ => {
+                return Err(err());
+            }
+        };
+
+        let mut total = Duration::zero();
+        let mut number = String::new();
+        for c in rest.chars() {
+            if c.is_ascii_digit() {
+                number.push(c);
+                continue;
+            }
+            let value: i64 = number.parse().map_err(|_| err())?;
+            let value = match c {
+                'h' => Duration::try_hours(value),
+                'm' => Duration::try_minutes(value),
+                _ => {
+                    return Err(err());
+                }
+            };
+            total = value.and_then(|v| total.checked_add(&v)).ok_or_else(err)?;
+            number.clear();
+        }
+        if !number.is_empty() || total.is_zero() {
+            return Err(err());
+        }
+        Ok(total * sign)
+    }
+
+    fn parse_time(s: &str) -> Result<NaiveTime, TrackerError> {
+        NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| TrackerError::TimeExpressionError {
+            message: format!("invalid time: {}, expected HH:MM", s),
+        })
+    }
+}
+
+impl FromStr for TimeExpression {
+    type Err = TrackerError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        let parts = s.split_whitespace().collect::<Vec<&str>>();
+        match parts.as_slice() {
+            ["now"] => Ok(TimeExpression::Now),
+            [r] if r.starts_with('-') || r.starts_with('+') => {
+                Ok(TimeExpression::Relative(TimeExpression::parse_relative(r)?))
+            }
+            [t] => Ok(TimeExpression::Time { days: 0, time: TimeExpression::parse_time(t)? }),
+            [day, t] => {
+                let time = TimeExpression::parse_time(t)?;
+                match *day {
+                    "today" => Ok(TimeExpression::Time { days: 0, time }),
+                    "yesterday" => Ok(TimeExpression::Time { days: -1, time }),
+                    "tomorrow" => Ok(TimeExpression::Time { days: 1, time }),
+                    d =>
+                        match NaiveDate::parse_from_str(d, "%Y-%m-%d") {
+                            Ok(date) => Ok(TimeExpression::DateTime(date.and_time(time))),
+                            Err(_) =>
+                                Err(TrackerError::TimeExpressionError {
+                                    message: format!("invalid day: {}, expected today, yesterday, tomorrow or YYYY-MM-DD", d),
+                                }),
+                        }
+                }
+            }
+            _ =>
+                Err(TrackerError::TimeExpressionError {
+                    message: format!("invalid time expression: {}", s),
+                }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{ Duration, TimeZone, Utc };
+    use chrono_tz::{ Asia::Tokyo, Europe::Berlin, UTC };
+
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2022, 2, 2, 12, 0, 0).unwrap()
+    }
+
+    fn utc() -> WorkTimeZone {
+        WorkTimeZone::from(UTC)
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn should_parse_time() {
+            let t = TimeExpression::from_str("08:15").unwrap();
+            assert_eq!(
+                TimeExpression::Time { days: 0, time: NaiveTime::from_hms_opt(8, 15, 0).unwrap() },
+                t
+            );
+        }
+
+        #[test]
+        fn should_parse_relative() {
+            assert_eq!(
+                TimeExpression::Relative(Duration::minutes(-10)),
+                TimeExpression::from_str("-10m").unwrap()
+            );
+            assert_eq!(
+                TimeExpression::Relative(Duration::minutes(90)),
+                TimeExpression::from_str("+1h30m").unwrap()
+            );
+        }
+
+        #[test]
+        fn should_parse_yesterday() {
+            let t = TimeExpression::from_str("yesterday 17:30").unwrap();
+            assert_eq!(
+                TimeExpression::Time { days: -1, time: NaiveTime::from_hms_opt(17, 30, 0).unwrap() },
+                t
+            );
+        }
+
+        #[test]
+        fn should_parse_date() {
+            let t = TimeExpression::from_str("2022-02-01 07:45").unwrap();
+            let expected = NaiveDate::from_ymd_opt(2022, 2, 1)
+                .unwrap()
+                .and_hms_opt(7, 45, 0)
+                .unwrap();
+            assert_eq!(TimeExpression::DateTime(expected), t);
+        }
+
+        #[test]
+        fn should_not_parse_invalid() {
+            assert!(TimeExpression::from_str("-10x").is_err());
+            assert!(TimeExpression::from_str("-").is_err());
+            assert!(TimeExpression::from_str("25:00").is_err());
+            assert!(TimeExpression::from_str("someday 08:00").is_err());
+            assert!(TimeExpression::from_str("").is_err());
+            assert!(TimeExpression::from_str("-99999999999999999h").is_err());
+            assert!(TimeExpression::from_str("+9223372036854775807m").is_err());
+        }
+    }
+
+    mod resolve {
+        use super::*;
+
+        #[test]
+        fn should_resolve_relative() {
+            let t = TimeExpression::from_str("-10m").unwrap().resolve(now(), &utc()).unwrap();
+            assert_eq!(now() - Duration::minutes(10), t);
+        }
+
+        #[test]
+        fn should_not_resolve_out_of_range() {
+            let t = TimeExpression::from_str("-2000000000000h").unwrap().resolve(now(), &utc());
+            assert_eq!("time expression error: relative time is out of range", t.err().unwrap().to_string());
+        }
+
+        #[test]
+        fn should_resolve_yesterday() {
+            let t = TimeExpression::from_str("yesterday 17:30").unwrap().resolve(now(), &utc()).unwrap();
+            assert_eq!(Utc.with_ymd_and_hms(2022, 2, 1, 17, 30, 0).unwrap(), t);
+        }
+
+        #[test]
+        fn should_resolve_day() {
+            let today = now().date_naive();
+            assert_eq!(
+                NaiveDate::from_ymd_opt(2022, 2, 1).unwrap(),
+                TimeExpression::day("yesterday", today).unwrap()
+            );
+            assert_eq!(
+                NaiveDate::from_ymd_opt(2021, 12, 24).unwrap(),
+                TimeExpression::day("2021-12-24", today).unwrap()
+            );
+            assert!(TimeExpression::day("08:00", today).is_err());
+        }
+
+        #[test]
+        fn should_resolve_time_of_today() {
+            let t = TimeExpression::from_str("08:15").unwrap().resolve(now(), &utc()).unwrap();
+            assert_eq!(Utc.with_ymd_and_hms(2022, 2, 2, 8, 15, 0).unwrap(), t);
+        }
+
+        #[test]
+        fn should_resolve_at_work_location() {
+            let zone = WorkTimeZone::from(Tokyo);
+            let t = TimeExpression::from_str("08:15").unwrap().resolve(now(), &zone).unwrap();
+            assert_eq!(Utc.with_ymd_and_hms(2022, 2, 1, 23, 15, 0).unwrap(), t);
+
+            // 23:00 UTC is already the next day in Tokyo
+            let late = Utc.with_ymd_and_hms(2022, 2, 2, 23, 0, 0).unwrap();
+            let t = TimeExpression::from_str("yesterday 17:30").unwrap().resolve(late, &zone).unwrap();
+            assert_eq!(Utc.with_ymd_and_hms(2022, 2, 2, 8, 30, 0).unwrap(), t);
+
+            let t = TimeExpression::from_str("-10m").unwrap().resolve(late, &zone).unwrap();
+            assert_eq!(late - Duration::minutes(10), t);
+        }
+
+        #[test]
+        fn should_not_resolve_skipped_time() {
+            let zone = WorkTimeZone::from(Berlin);
+            let t = TimeExpression::from_str("2022-03-27 02:30").unwrap().resolve(now(), &zone);
+            assert!(t.is_err());
+        }
+    }
+}
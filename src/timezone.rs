@@ -0,0 +1,105 @@
+use std::fmt::Display;
+
+use chrono::{ DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, TimeZone, Utc };
+use chrono_tz::Tz;
+use serde::{ Deserialize, Serialize };
+
+/// IANA timezone of the work location, the system timezone when not configured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WorkTimeZone(pub Option<Tz>);
+
+impl WorkTimeZone {
+    /// Wall clock time of `time` at the work location.
+    pub fn local(&self, time: DateTime<Utc>) -> NaiveDateTime {
+        match self.0 {
+            Some(tz) => time.with_timezone(&tz).naive_local(),
+            None => time.with_timezone(&Local).naive_local(),
+        }
+    }
+
+    /// Offset to UTC at `time`, which changes on daylight saving time switches.
+    pub fn offset(&self, time: DateTime<Utc>) -> Duration {
+        self.local(time) - time.naive_utc()
+    }
+
+    /// `time` with the offset of the work location, eg. for RFC 3339 output.
+    pub fn fixed(&self, time: DateTime<Utc>) -> DateTime<FixedOffset> {
+        let seconds = self.offset(time).num_seconds() as i32;
+        time.with_timezone(&FixedOffset::east_opt(seconds).unwrap_or(Utc.fix()))
+    }
+
+    /// Day `time` falls into at the work location.
+    pub fn date(&self, time: DateTime<Utc>) -> NaiveDate {
+        self.local(time).date()
+    }
+
+    /// Point in time of a wall clock time at the work location, the earlier one if it is ambiguous.
+    pub fn utc(&self, local: NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self.0 {
+            Some(tz) => tz.from_local_datetime(&local).earliest().map(|t| t.to_utc()),
+            None => Local.from_local_datetime(&local).earliest().map(|t| t.to_utc()),
+        }
+    }
+
+    pub fn today(&self) -> NaiveDate {
+        self.date(Utc::now())
+    }
+}
+
+impl From<Tz> for WorkTimeZone {
+    fn from(tz: Tz) -> Self {
+        WorkTimeZone(Some(tz))
+    }
+}
+
+impl Display for WorkTimeZone {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Some(tz) => write!(f, "{}", tz.name()),
+            None => write!(f, "system"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Timelike;
+    use chrono_tz::Europe::Berlin;
+
+    use super::*;
+
+    #[test]
+    fn should_convert_to_wall_clock() {
+        let zone = WorkTimeZone::from(Berlin);
+        let winter = Utc.with_ymd_and_hms(2022, 2, 2, 23, 30, 0).unwrap();
+        let summer = Utc.with_ymd_and_hms(2022, 7, 2, 8, 0, 0).unwrap();
+
+        assert_eq!(0, zone.local(winter).hour());
+        assert_eq!(NaiveDate::from_ymd_opt(2022, 2, 3).unwrap(), zone.date(winter));
+        assert_eq!(Duration::hours(1), zone.offset(winter));
+        assert_eq!(10, zone.local(summer).hour());
+        assert_eq!(Duration::hours(2), zone.offset(summer));
+        assert_eq!("2022-07-02T10:00:00+02:00", zone.fixed(summer).to_rfc3339());
+    }
+
+    #[test]
+    fn should_convert_from_wall_clock() {
+        let zone = WorkTimeZone::from(Berlin);
+        let time = |d: u32, h: u32, m: u32| NaiveDate::from_ymd_opt(2022, 3, d).unwrap().and_hms_opt(h, m, 0).unwrap();
+
+        assert_eq!(Some(Utc.with_ymd_and_hms(2022, 3, 1, 7, 0, 0).unwrap()), zone.utc(time(1, 8, 0)));
+        assert_eq!(None, zone.utc(time(27, 2, 30)));
+    }
+
+    #[test]
+    fn should_deserialize() {
+        let zone: WorkTimeZone = serde_json::from_str("\"Europe/Berlin\"").unwrap();
+        assert_eq!(WorkTimeZone::from(Berlin), zone);
+        assert_eq!("Europe/Berlin", zone.to_string());
+
+        let zone: WorkTimeZone = serde_json::from_str("null").unwrap();
+        assert_eq!(WorkTimeZone::default(), zone);
+        assert!(serde_json::from_str::<WorkTimeZone>("\"Europe/Nowhere\"").is_err());
+    }
+}
@@ -4,9 +4,13 @@ use std::{
     io,
 };
 
+use chrono::{Datelike, NaiveDate};
 use clap::Parser;
 use serial_test::serial;
-use trackrs::{Cli, CliExecute, Settings};
+use trackrs::{
+    Cli, CliExecute, OutputFormat, RuleCheck, RuleSeverity, Settings, StatusDaily, StatusRange, StatusWeekly,
+    TimeData, TimeDataRange, TimeDataWeekly, CONFIG_ENV,
+};
 
 fn logger() {
     // env::set_var("RUST_LOG", "info");
@@ -20,9 +24,8 @@ struct IntegrationContext {
 impl test_context::TestContext for IntegrationContext {
     fn setup() -> IntegrationContext {
         logger();
-        env::set_var("RUST_TEST", "true");
         let temp_dir = tempfile::tempdir().unwrap();
-        env::set_current_dir(&temp_dir).unwrap();
+        env::set_var(CONFIG_ENV, temp_dir.path().join(".trackrs"));
         let mut settings = Settings::new().unwrap();
         let trackrs_folder = temp_dir.path().join("trackrs");
         settings.folder = trackrs_folder.to_str().unwrap().to_owned();
@@ -55,7 +58,11 @@ fn start_break_continue_and_end_workflow(ctx: &mut IntegrationContext) {
     let files = f
         .map(|res| res.map(|e| e.path()))
         .collect::<Result<Vec<_>, io::Error>>()
-        .unwrap();
+        .unwrap()
+        .into_iter()
+        // the lock file is kept next to the time file
+        .filter(|f| f.extension().is_some_and(|e| e == "json"))
+        .collect::<Vec<_>>();
     assert_eq!(&1, &files.len());
 
     let b = Cli::parse_from(["trackrs", "break"].iter());
@@ -94,7 +101,11 @@ fn start_break_continue_workflow(ctx: &mut IntegrationContext) {
     let files = f
         .map(|res| res.map(|e| e.path()))
         .collect::<Result<Vec<_>, io::Error>>()
-        .unwrap();
+        .unwrap()
+        .into_iter()
+        // the lock file is kept next to the time file
+        .filter(|f| f.extension().is_some_and(|e| e == "json"))
+        .collect::<Vec<_>>();
     assert_eq!(&1, &files.len());
 
     let b = Cli::parse_from(["trackrs", "break"].iter());
@@ -130,7 +141,11 @@ fn takeover_subtracts_from_today(ctx: &mut IntegrationContext) {
     let files = f
         .map(|res| res.map(|e| e.path()))
         .collect::<Result<Vec<_>, io::Error>>()
-        .unwrap();
+        .unwrap()
+        .into_iter()
+        // the lock file is kept next to the time file
+        .filter(|f| f.extension().is_some_and(|e| e == "json"))
+        .collect::<Vec<_>>();
     assert_eq!(&1, &files.len());
 
     let b = Cli::parse_from(["trackrs", "break"].iter());
@@ -152,3 +167,522 @@ fn takeover_subtracts_from_today(ctx: &mut IntegrationContext) {
     assert!(a.contains("\"id\":3,\"status\":\"Connect\""));
     assert!(!a.contains("\"status\":\"End\""));
 }
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn back_dated_workflow(ctx: &mut IntegrationContext) {
+    let file = ctx.temp_dir.path().join("trackrs").join("20220202.json");
+
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02", "08:00"].iter());
+    s.execute().unwrap();
+    assert!(file.exists());
+
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 16:30"].iter());
+    e.execute().unwrap();
+
+    let c = Cli::parse_from(["trackrs", "continue", "--at", "2022-02-02 17:00"].iter());
+    assert!(c.execute().is_err());
+
+    let a = fs::read_to_string(file).unwrap();
+
+    assert!(a.contains("\"id\":1,\"status\":\"Connect\",\"time\":\"2022-02-02T"));
+    assert!(a.contains("\"status\":\"End\",\"time\":\"2022-02-02T"));
+    assert!(!a.contains("\"id\":3"));
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn edit_workflow(ctx: &mut IntegrationContext) {
+    let file = ctx.temp_dir.path().join("trackrs").join("20220202.json");
+
+    for (command, time) in [
+        ("start", "08:00"),
+        ("break", "12:00"),
+        ("continue", "12:30"),
+        ("end", "16:30"),
+    ] {
+        let at = format!("2022-02-02 {}", time);
+        let c = Cli::parse_from(["trackrs", command, "--at", &at].iter());
+        c.execute().unwrap();
+    }
+
+    let d = Cli::parse_from(["trackrs", "edit", "--day", "2022-02-02", "delete", "2"].iter());
+    d.execute().unwrap();
+    let a = fs::read_to_string(&file).unwrap();
+    assert!(!a.contains("\"status\":\"Break\""));
+    assert!(a.contains("\"id\":3,\"status\":\"End\""));
+
+    let s = Cli::parse_from(
+        ["trackrs", "edit", "--day", "2022-02-02", "set", "2", "--status", "break"].iter(),
+    );
+    s.execute().unwrap();
+    let i = Cli::parse_from(
+        ["trackrs", "edit", "--day", "2022-02-02", "insert", "connect", "12:45"].iter(),
+    );
+    i.execute().unwrap();
+
+    let a = fs::read_to_string(&file).unwrap();
+    assert!(a.contains("\"id\":2,\"status\":\"Break\""));
+    assert!(a.contains("\"id\":3,\"status\":\"Connect\""));
+    assert!(a.contains("\"id\":4,\"status\":\"End\""));
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn takeover_persists_minutes_for_next_day(ctx: &mut IntegrationContext) {
+    let folder = ctx.temp_dir.path().join("trackrs");
+    let takeover_file = ctx.temp_dir.path().join("takeover.json");
+
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+
+    let t = Cli::parse_from(["trackrs", "takeover", "30", "--at", "2022-02-02 16:30"].iter());
+    t.execute().unwrap();
+
+    let a = fs::read_to_string(folder.join("20220202.json")).unwrap();
+    assert!(a.contains("\"status\":\"End\""));
+    assert!(a.contains("\"status\":\"Takeover\""));
+    assert_eq!(
+        "{\"minutes\":30}",
+        fs::read_to_string(&takeover_file).unwrap()
+    );
+
+    let n = Cli::parse_from(["trackrs", "start", "--at", "2022-02-03 08:00"].iter());
+    n.execute().unwrap();
+
+    let a = fs::read_to_string(folder.join("20220203.json")).unwrap();
+    assert!(a.contains("\"id\":1,\"status\":\"Connect\""));
+    assert!(a.contains("\"id\":2,\"status\":\"Connect\""));
+    assert!(!takeover_file.exists());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn report_for_range(_ctx: &mut IntegrationContext) {
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 16:00"].iter());
+    e.execute().unwrap();
+
+    let r = Cli::parse_from(
+        ["trackrs", "report", "--from", "2022-02-01", "--to", "2022-02-03"].iter(),
+    );
+    r.execute().unwrap();
+
+    let settings = Settings::new().unwrap();
+    let status = StatusRange::builder()
+        .data(
+            TimeDataRange::builder()
+                .folder(settings.folder.to_owned().into())
+                .from(NaiveDate::from_ymd_opt(2022, 2, 1).unwrap())
+                .to(NaiveDate::from_ymd_opt(2022, 2, 3).unwrap())
+                .build()
+                .unwrap(),
+        )
+        .settings(settings)
+        .build()
+        .unwrap();
+    let report = status.to_string();
+    let totals = report.lines().nth(2).unwrap().split('|').map(str::trim).collect::<Vec<&str>>();
+    assert_eq!(vec!["2022-02-01", "2022-02-03", "08:00", "24:00"], totals[..4]);
+    assert!(totals[4].contains("-16:00"));
+    assert_eq!("8.00", totals[5]);
+
+    let t = Cli::parse_from(
+        ["trackrs", "report", "--from", "2022-02-03", "--to", "2022-02-01"].iter(),
+    );
+    assert!(t.execute().is_err());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn balance_is_cached(ctx: &mut IntegrationContext) {
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 16:00"].iter());
+    e.execute().unwrap();
+
+    let b = Cli::parse_from(["trackrs", "balance", "--until", "2022-02-03"].iter());
+    b.execute().unwrap();
+    assert!(ctx.temp_dir.path().join("trackrs/.trackrs-balance").exists());
+
+    let s = Cli::parse_from(["trackrs", "start"].iter());
+    s.execute().unwrap();
+    let t = Cli::parse_from(["trackrs", "status"].iter());
+    t.execute().unwrap();
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn absence_credits_report(ctx: &mut IntegrationContext) {
+    let a = Cli::parse_from(
+        ["trackrs", "absence", "add", "--type", "vacation", "--from", "2022-02-04", "--to", "2022-02-07"].iter(),
+    );
+    a.execute().unwrap();
+    assert!(ctx.temp_dir.path().join("trackrs/.trackrs-absences").exists());
+
+    let l = Cli::parse_from(["trackrs", "absence", "list", "--year", "2022"].iter());
+    l.execute().unwrap();
+
+    let r = Cli::parse_from(["trackrs", "absence", "remove", "--from", "2022-02-07"].iter());
+    r.execute().unwrap();
+
+    let t = Cli::parse_from(["trackrs", "absence", "add", "-t", "sick", "--from", "2022-02-07", "--to", "2022-02-04"].iter());
+    assert!(t.execute().is_err());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn switch_projects(ctx: &mut IntegrationContext) {
+    let s = Cli::parse_from(["trackrs", "start", "-p", "foo", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+    let w = Cli::parse_from(["trackrs", "switch", "bar", "--tag", "review", "--at", "2022-02-02 10:00"].iter());
+    w.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 16:00"].iter());
+    e.execute().unwrap();
+
+    let content = fs::read_to_string(ctx.temp_dir.path().join("trackrs/20220202.json")).unwrap();
+    assert!(content.contains("\"project\":\"foo\""));
+    assert!(content.contains("\"project\":\"bar\",\"tags\":[\"review\"]"));
+    assert!(content.contains("\"version\":3"));
+
+    let t = Cli::parse_from(["trackrs", "status", "-w", "5"].iter());
+    t.execute().unwrap();
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn notes_for_segment_and_day(ctx: &mut IntegrationContext) {
+    let n = Cli::parse_from(["trackrs", "note", "too early", "--at", "2022-02-02 07:00"].iter());
+    assert!(n.execute().is_err());
+
+    let s = Cli::parse_from(
+        ["trackrs", "start", "-m", "sprint planning", "--at", "2022-02-02 08:00"].iter(),
+    );
+    s.execute().unwrap();
+    let n = Cli::parse_from(
+        ["trackrs", "note", "release day", "--day", "--at", "2022-02-02 09:00"].iter(),
+    );
+    n.execute().unwrap();
+
+    let content = fs::read_to_string(ctx.temp_dir.path().join("trackrs/20220202.json")).unwrap();
+    assert!(content.contains("\"note\":\"sprint planning\""));
+    assert!(content.contains("\"note\":\"release day\""));
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn night_shift_stays_in_start_day(ctx: &mut IntegrationContext) {
+    let folder = ctx.temp_dir.path().join("trackrs");
+    for (command, at) in [
+        ("start", "2022-02-02 22:00"),
+        ("break", "2022-02-03 01:00"),
+        ("continue", "2022-02-03 01:30"),
+        ("end", "2022-02-03 06:30"),
+    ] {
+        let c = Cli::parse_from(["trackrs", command, "--at", at].iter());
+        c.execute().unwrap();
+    }
+
+    let content = fs::read_to_string(folder.join("20220202.json")).unwrap();
+    assert!(content.contains("\"id\":4,\"status\":\"End\""));
+    assert!(!folder.join("20220203.json").exists());
+
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-03 22:00"].iter());
+    s.execute().unwrap();
+    assert!(folder.join("20220203.json").exists());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn export_csv(ctx: &mut IntegrationContext) {
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 16:00"].iter());
+    e.execute().unwrap();
+
+    let file = ctx.temp_dir.path().join("export.csv");
+    let mut exports = Vec::new();
+    for mode in ["entries", "days"] {
+        let x = Cli::parse_from(
+            [
+                "trackrs", "export", "-m", mode, "-f", "2022-02-01", "-u", "2022-02-02", "-d", ";",
+                "-o", file.to_str().unwrap(),
+            ]
+            .iter(),
+        );
+        x.execute().unwrap();
+        exports.push(fs::read_to_string(&file).unwrap());
+    }
+    assert_eq!(
+        "date;id;status;time\n\
+         2022-02-02;1;Connect;2022-02-02 08:00:00\n\
+         2022-02-02;2;End;2022-02-02 16:00:00\n",
+        exports[0]
+    );
+    assert_eq!(
+        "date;start;end;break;worktime;overtime\n\
+         2022-02-01;;;;00:00;-08:00\n\
+         2022-02-02;08:00;16:00;00:00;08:00;00:00\n",
+        exports[1]
+    );
+
+    let x = Cli::parse_from(
+        ["trackrs", "export", "-f", "2022-02-02", "-u", "2022-02-02", "-c", "date,worktime"].iter(),
+    );
+    assert!(x.execute().is_err());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn export_ics(ctx: &mut IntegrationContext) {
+    let file = ctx.temp_dir.path().join(".trackrs");
+    let mut settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    settings["timezone"] = serde_json::json!("Europe/Berlin");
+    fs::write(&file, settings.to_string()).unwrap();
+
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+    let b = Cli::parse_from(["trackrs", "break", "--at", "2022-02-02 12:00"].iter());
+    b.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 16:00"].iter());
+    e.execute().unwrap();
+
+    let file = ctx.temp_dir.path().join("export.ics");
+    let x = Cli::parse_from(
+        [
+            "trackrs", "export", "--format", "ics", "-b", "-f", "2022-02-01", "-u", "2022-02-02", "-o",
+            file.to_str().unwrap(),
+        ]
+        .iter(),
+    );
+    x.execute().unwrap();
+
+    let ics = fs::read_to_string(&file).unwrap();
+    assert!(ics.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert_eq!(2, ics.matches("BEGIN:VEVENT\r\n").count());
+    // times are exported as UTC
+    assert!(ics.contains("UID:20220202-1@trackrs\r\n"));
+    assert!(ics.contains("DTSTART:20220202T070000Z\r\nDTEND:20220202T110000Z\r\nSUMMARY:Work\r\n"));
+    assert!(ics.contains("DTSTART:20220202T110000Z\r\nDTEND:20220202T150000Z\r\nSUMMARY:Break\r\n"));
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn import_csv(ctx: &mut IntegrationContext) {
+    let folder = ctx.temp_dir.path().join("trackrs");
+    let file = ctx.temp_dir.path().join("import.csv");
+    fs::write(&file, "start,end,project\n2022-02-02 08:00,2022-02-02 16:00,foo\n").unwrap();
+
+    let x = Cli::parse_from(
+        ["trackrs", "import", "--format", "csv", "--dry-run", file.to_str().unwrap()].iter(),
+    );
+    x.execute().unwrap();
+    assert!(!folder.join("20220202.json").exists());
+
+    let x = Cli::parse_from(["trackrs", "import", "--format", "csv", file.to_str().unwrap()].iter());
+    x.execute().unwrap();
+    let content = fs::read_to_string(folder.join("20220202.json")).unwrap();
+    assert!(content.contains("\"project\":\"foo\""));
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn status_output(_ctx: &mut IntegrationContext) {
+    for output in ["json", "yaml"] {
+        let x = Cli::parse_from(["trackrs", "status", "-o", output].iter());
+        x.execute().unwrap();
+    }
+
+    let s = Cli::parse_from(["trackrs", "start", "--at", "-1h"].iter());
+    s.execute().unwrap();
+    for args in [vec!["trackrs", "status", "-o", "json"], vec!["trackrs", "status", "-w", "0", "-o", "yaml"]] {
+        let x = Cli::parse_from(args.iter());
+        x.execute().unwrap();
+    }
+
+    assert!(Cli::try_parse_from(["trackrs", "status", "-m", "0", "-o", "json"].iter()).is_err());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn status_output_keys(_ctx: &mut IntegrationContext) {
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 16:00"].iter());
+    e.execute().unwrap();
+
+    let settings = Settings::new().unwrap();
+    let day = NaiveDate::from_ymd_opt(2022, 2, 2).unwrap();
+    let mut time_data = TimeData::builder()
+        .folder(settings.folder.to_owned().into())
+        .day(day)
+        .build()
+        .unwrap();
+    time_data.read_only().unwrap();
+    let daily = StatusDaily::builder()
+        .data(time_data)
+        .settings(settings.to_owned())
+        .build()
+        .unwrap();
+    let json = OutputFormat::Json.serialize(&daily.output()).unwrap().unwrap();
+    let daily: serde_json::Value = serde_json::from_str(&json).unwrap();
+    for key in ["start", "end", "est_end", "break", "exp_break", "online", "worktime", "overtime"] {
+        assert!(daily.get(key).is_some(), "missing daily key {}", key);
+    }
+    assert_eq!(480, daily["online"]);
+
+    let weekly = StatusWeekly::builder()
+        .data(
+            TimeDataWeekly::builder()
+                .folder(settings.folder.to_owned().into())
+                .year(2022)
+                .week(&5, day.iso_week())
+                .build()
+                .unwrap(),
+        )
+        .settings(settings)
+        .build()
+        .unwrap();
+    let json = OutputFormat::Json.serialize(&weekly.output()).unwrap().unwrap();
+    let weekly: serde_json::Value = serde_json::from_str(&json).unwrap();
+    for key in ["worktime", "overtime", "days"] {
+        assert!(weekly.get(key).is_some(), "missing weekly key {}", key);
+    }
+    let days = weekly["days"].as_array().unwrap();
+    let day = days.iter().find(|d| d["date"] == "2022-02-02").unwrap();
+    assert_eq!(daily["worktime"], day["worktime"]);
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn rules_at_end(ctx: &mut IntegrationContext) {
+    let file = ctx.temp_dir.path().join(".trackrs");
+    let mut settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    settings["rules"] = serde_json::json!({ "min_break": 15, "max_block": 360, "max_worktime": 600, "min_rest": 660 });
+    fs::write(&file, settings.to_string()).unwrap();
+
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-01 14:00"].iter());
+    s.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-01 23:00"].iter());
+    e.execute().unwrap();
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 06:00"].iter());
+    s.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--at", "2022-02-02 14:00"].iter());
+    e.execute().unwrap();
+
+    let settings = Settings::new().unwrap();
+    let read = |day: u32| {
+        let mut t = TimeData::builder()
+            .folder(settings.folder.to_owned().into())
+            .day(NaiveDate::from_ymd_opt(2022, 2, day).unwrap())
+            .build()
+            .unwrap();
+        t.read_only().unwrap();
+        t
+    };
+    let check = RuleCheck::builder()
+        .data(read(2))
+        .previous(read(1))
+        .settings(settings.to_owned())
+        .build()
+        .unwrap();
+    let messages = check.findings.iter().map(|f| f.message.as_str()).collect::<Vec<&str>>();
+    assert!(messages.contains(&"worked 08:00 without a break since 06:00"));
+    assert!(messages.contains(&"rest of 07:00 since the previous day is less than 11:00"));
+    assert!(check.findings.iter().all(|f| f.severity == RuleSeverity::Violation));
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn profiles(ctx: &mut IntegrationContext) {
+    let file = ctx.temp_dir.path().join(".trackrs");
+    let client = ctx.temp_dir.path().join("client");
+    let mut settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    settings["profiles"] = serde_json::json!({ "client": { "folder": client.to_str().unwrap() } });
+    fs::write(&file, settings.to_string()).unwrap();
+
+    let s = Cli::parse_from(["trackrs", "--profile", "client", "start", "--at", "2022-02-01 08:00"].iter());
+    s.execute().unwrap();
+    let e = Cli::parse_from(["trackrs", "end", "--profile", "client", "--at", "2022-02-01 16:30"].iter());
+    e.execute().unwrap();
+    assert!(client.join("20220201.json").exists());
+    assert!(!ctx.temp_dir.path().join("trackrs").join("20220201.json").exists());
+
+    let r = Cli::parse_from(["trackrs", "report", "--combined", "--from", "2022-02-01", "--to", "2022-02-01"].iter());
+    r.execute().unwrap();
+
+    let x = Cli::parse_from(["trackrs", "--profile", "none", "status"].iter());
+    assert!(x.execute().is_err());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn config_flag(ctx: &mut IntegrationContext) {
+    let config = ctx.temp_dir.path().join("other").join("config.json");
+    let folder = ctx.temp_dir.path().join("other-folder");
+    fs::create_dir_all(config.parent().unwrap()).unwrap();
+    fs::write(&config, serde_json::json!({ "folder": folder.to_str().unwrap() }).to_string()).unwrap();
+
+    let s = Cli::parse_from(["trackrs", "--config", config.to_str().unwrap(), "start", "--at", "2022-02-01 08:00"].iter());
+    s.execute().unwrap();
+    assert!(folder.join("20220201.json").exists());
+    assert!(!ctx.temp_dir.path().join("trackrs").join("20220201.json").exists());
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn config_check(ctx: &mut IntegrationContext) {
+    let c = Cli::parse_from(["trackrs", "config", "check"].iter());
+    c.execute().unwrap();
+
+    let file = ctx.temp_dir.path().join(".trackrs");
+    let mut settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    settings["limits"] = serde_json::json!([{ "start": 360, "minutes": 30 }, { "start": 370, "minutes": 45 }]);
+    fs::write(&file, settings.to_string()).unwrap();
+
+    let c = Cli::parse_from(["trackrs", "config", "check"].iter());
+    assert!(c.execute().is_err());
+    let s = Cli::parse_from(["trackrs", "status"].iter());
+    let error = s.execute().unwrap_err().to_string();
+    assert!(error.contains("limits[1].start: 370 overlaps the break of limits[0]"), "{}", error);
+    let l = Cli::parse_from(["trackrs", "config", "--list"].iter());
+    l.execute().unwrap();
+}
+
+#[test_context::test_context(IntegrationContext)]
+#[test]
+#[serial]
+fn times_at_configured_timezone(ctx: &mut IntegrationContext) {
+    let file = ctx.temp_dir.path().join(".trackrs");
+    let mut settings: serde_json::Value = serde_json::from_str(&fs::read_to_string(&file).unwrap()).unwrap();
+    settings["timezone"] = serde_json::json!("Asia/Tokyo");
+    fs::write(&file, settings.to_string()).unwrap();
+
+    let s = Cli::parse_from(["trackrs", "start", "--at", "2022-02-02 08:00"].iter());
+    s.execute().unwrap();
+    let i = Cli::parse_from(["trackrs", "edit", "--day", "2022-02-02", "insert", "break", "12:00"].iter());
+    i.execute().unwrap();
+
+    let data = fs::read_to_string(ctx.temp_dir.path().join("trackrs").join("20220202.json")).unwrap();
+    assert!(data.contains("2022-02-01T23:00:00Z"), "{}", data);
+    assert!(data.contains("2022-02-02T03:00:00Z"), "{}", data);
+}